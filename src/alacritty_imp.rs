@@ -0,0 +1,388 @@
+use std::borrow::Cow;
+
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line},
+    term::{
+        cell::{Cell as TermCell, Flags},
+        TermMode,
+    },
+    vte::ansi::{Color as AnsiColor, CursorShape as AnsiCursorShape, NamedColor},
+    Term,
+};
+use ratatui::style::{Color, Modifier};
+
+use crate::widget::{Cell, CursorShape, MouseProtocolEncoding, MouseProtocolMode, Screen};
+
+// Lines of the grid are counted from the top of the screen,
+// the scrollback history is found at the negative lines above it.
+impl<T> Screen for Term<T> {
+    type C = TermCell;
+
+    #[inline]
+    fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+        let grid = self.grid();
+        if usize::from(row) >= grid.screen_lines() || usize::from(col) >= grid.columns() {
+            return None;
+        }
+        Some(&grid[Line(i32::from(row))][Column(usize::from(col))])
+    }
+
+    #[inline]
+    fn hide_cursor(&self) -> bool {
+        !self.mode().contains(TermMode::SHOW_CURSOR)
+    }
+
+    #[inline]
+    fn cursor_position(&self) -> (u16, u16) {
+        let point = self.grid().cursor.point;
+        (
+            u16::try_from(point.line.0).unwrap_or_default(),
+            u16::try_from(point.column.0).unwrap_or(u16::MAX),
+        )
+    }
+
+    #[inline]
+    fn scrollback_len(&self) -> usize {
+        self.grid().history_size()
+    }
+
+    #[inline]
+    fn scrollback_cell(&self, row: usize, col: u16) -> Option<&Self::C> {
+        let grid = self.grid();
+        if row >= grid.history_size() || usize::from(col) >= grid.columns() {
+            return None;
+        }
+        let line = Line(-i32::try_from(row).ok()? - 1);
+        Some(&grid[line][Column(usize::from(col))])
+    }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        let last_col = u16::try_from(self.grid().last_column().0).unwrap_or(u16::MAX);
+        self.cell(row, last_col)
+            .is_some_and(|cell| cell.flags.contains(Flags::WRAPLINE))
+    }
+
+    #[inline]
+    fn scrollback_row_wrapped(&self, row: usize) -> bool {
+        let last_col = u16::try_from(self.grid().last_column().0).unwrap_or(u16::MAX);
+        self.scrollback_cell(row, last_col)
+            .is_some_and(|cell| cell.flags.contains(Flags::WRAPLINE))
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        let grid = self.grid();
+        (
+            u16::try_from(grid.screen_lines()).unwrap_or(u16::MAX),
+            u16::try_from(grid.columns()).unwrap_or(u16::MAX),
+        )
+    }
+
+    // The title is handed to the event listener instead of being kept in the `Term`.
+
+    #[inline]
+    fn alternate_screen(&self) -> bool {
+        self.mode().contains(TermMode::ALT_SCREEN)
+    }
+
+    #[inline]
+    fn application_cursor(&self) -> bool {
+        self.mode().contains(TermMode::APP_CURSOR)
+    }
+
+    #[inline]
+    fn application_keypad(&self) -> bool {
+        self.mode().contains(TermMode::APP_KEYPAD)
+    }
+
+    #[inline]
+    fn bracketed_paste(&self) -> bool {
+        self.mode().contains(TermMode::BRACKETED_PASTE)
+    }
+
+    // `alacritty_terminal` does not support the X10 mode `9`.
+    #[inline]
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        let mode = self.mode();
+        if mode.contains(TermMode::MOUSE_MOTION) {
+            MouseProtocolMode::AnyMotion
+        } else if mode.contains(TermMode::MOUSE_DRAG) {
+            MouseProtocolMode::ButtonMotion
+        } else if mode.contains(TermMode::MOUSE_REPORT_CLICK) {
+            MouseProtocolMode::PressRelease
+        } else {
+            MouseProtocolMode::None
+        }
+    }
+
+    #[inline]
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        let mode = self.mode();
+        if mode.contains(TermMode::SGR_MOUSE) {
+            MouseProtocolEncoding::Sgr
+        } else if mode.contains(TermMode::UTF8_MOUSE) {
+            MouseProtocolEncoding::Utf8
+        } else {
+            MouseProtocolEncoding::Default
+        }
+    }
+
+    // The configured default style is reported if the application did not request one.
+    #[inline]
+    fn cursor_shape(&self) -> Option<CursorShape> {
+        match self.cursor_style().shape {
+            AnsiCursorShape::Block | AnsiCursorShape::HollowBlock => Some(CursorShape::Block),
+            AnsiCursorShape::Underline => Some(CursorShape::Underline),
+            AnsiCursorShape::Beam => Some(CursorShape::Bar),
+            AnsiCursorShape::Hidden => None,
+        }
+    }
+}
+
+impl Cell for TermCell {
+    #[inline]
+    fn has_contents(&self) -> bool {
+        // Cells are filled with spaces, so written spaces cannot be told apart from empty cells.
+        // Tabs are kept in the cells they skipped over, but are not drawn.
+        let spacer = self
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER);
+        !spacer && (!matches!(self.c, ' ' | '\t') || self.zerowidth().is_some())
+    }
+
+    #[inline]
+    fn symbol(&self) -> Cow<'_, str> {
+        if !self.has_contents() {
+            return Cow::Borrowed(" ");
+        }
+        let zerowidth = self.zerowidth().unwrap_or_default();
+        Cow::Owned(
+            std::iter::once(self.c)
+                .chain(zerowidth.iter().copied())
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn fg(&self) -> Color {
+        convert_color(self.fg)
+    }
+
+    #[inline]
+    fn bg(&self) -> Color {
+        convert_color(self.bg)
+    }
+
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        let flags = self.flags;
+        let mut modifiers = Modifier::empty();
+        if flags.contains(Flags::BOLD) {
+            modifiers |= Modifier::BOLD;
+        }
+        if flags.contains(Flags::DIM) {
+            modifiers |= Modifier::DIM;
+        }
+        if flags.contains(Flags::ITALIC) {
+            modifiers |= Modifier::ITALIC;
+        }
+        if flags.intersects(Flags::ALL_UNDERLINES) {
+            modifiers |= Modifier::UNDERLINED;
+        }
+        if flags.contains(Flags::INVERSE) {
+            modifiers |= Modifier::REVERSED;
+        }
+        if flags.contains(Flags::HIDDEN) {
+            modifiers |= Modifier::HIDDEN;
+        }
+        if flags.contains(Flags::STRIKEOUT) {
+            modifiers |= Modifier::CROSSED_OUT;
+        }
+        modifiers
+    }
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.flags.contains(Flags::WIDE_CHAR)
+    }
+
+    #[inline]
+    fn is_wide_continuation(&self) -> bool {
+        self.flags.contains(Flags::WIDE_CHAR_SPACER)
+    }
+}
+
+/// Converts the color of a cell, named colors are mapped to the indexed colors
+/// that the `vt100` backend produces for the same escape sequences.
+#[inline]
+fn convert_color(color: AnsiColor) -> Color {
+    match color {
+        AnsiColor::Spec(rgb) => Color::Rgb(rgb.r, rgb.g, rgb.b),
+        AnsiColor::Indexed(i) => Color::Indexed(i),
+        AnsiColor::Named(named) => match named {
+            NamedColor::Foreground
+            | NamedColor::Background
+            | NamedColor::Cursor
+            | NamedColor::BrightForeground
+            | NamedColor::DimForeground => Color::Reset,
+            NamedColor::DimBlack => Color::Indexed(0),
+            NamedColor::DimRed => Color::Indexed(1),
+            NamedColor::DimGreen => Color::Indexed(2),
+            NamedColor::DimYellow => Color::Indexed(3),
+            NamedColor::DimBlue => Color::Indexed(4),
+            NamedColor::DimMagenta => Color::Indexed(5),
+            NamedColor::DimCyan => Color::Indexed(6),
+            NamedColor::DimWhite => Color::Indexed(7),
+            // The remaining named colors are the 16 ANSI colors
+            named => Color::Indexed(named as u8),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::{test::TermSize, Config},
+        vte::ansi::Processor,
+    };
+
+    use super::*;
+    use crate::testing::snapshot_screen;
+
+    fn term(rows: usize, cols: usize, stream: &[u8]) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(cols, rows), VoidListener);
+        let mut processor: Processor = Processor::new();
+        processor.advance(&mut term, stream);
+        term
+    }
+
+    fn snapshot_typescript(stream: &[u8]) -> String {
+        snapshot_screen(&term(24, 80, stream))
+    }
+
+    #[test]
+    fn scrollback() {
+        let term = term(2, 10, b"1\r\n2\r\n3\r\n4");
+        assert_eq!(term.scrollback_len(), 2);
+        assert_eq!(term.scrollback_cell(0, 0).unwrap().c, '2');
+        assert_eq!(term.scrollback_cell(1, 0).unwrap().c, '1');
+        assert_eq!(Screen::cell(&term, 1, 0).unwrap().c, '4');
+        assert!(term.scrollback_cell(2, 0).is_none());
+    }
+
+    #[test]
+    fn row_wrapped() {
+        let term = term(3, 10, b"0123456789abc");
+        assert!(term.row_wrapped(0));
+        assert!(!term.row_wrapped(1));
+    }
+
+    #[test]
+    fn modes() {
+        let term = term(
+            3,
+            10,
+            b"\x1b[?1h\x1b=\x1b[?2004h\x1b[?1002h\x1b[?1006h\x1b[?1049h",
+        );
+        assert_eq!(Screen::size(&term), (3, 10));
+        assert!(Screen::alternate_screen(&term));
+        assert!(Screen::application_cursor(&term));
+        assert!(Screen::application_keypad(&term));
+        assert!(Screen::bracketed_paste(&term));
+        assert_eq!(
+            Screen::mouse_protocol_mode(&term),
+            MouseProtocolMode::ButtonMotion
+        );
+        assert_eq!(
+            Screen::mouse_protocol_encoding(&term),
+            MouseProtocolEncoding::Sgr
+        );
+    }
+
+    #[test]
+    fn cursor_shape() {
+        let term = term(3, 10, b"\x1b[5 q");
+        assert_eq!(Screen::cursor_shape(&term), Some(CursorShape::Bar));
+    }
+
+    #[test]
+    fn simple_ls() {
+        let stream = include_bytes!("../test/typescript/simple_ls.typescript");
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn overlapping_cursor() {
+        let stream = include_bytes!("../test/typescript/overlapping_cursor.typescript");
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn italic_text() {
+        let stream =
+            b"\x1b[3mThis line will be displayed in italic.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn underlined_text() {
+        let stream =
+            b"\x1b[4mThis line will be displayed with an underline.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn bold_text() {
+        let stream = b"\x1b[1mThis line will be displayed bold.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn inverse_text() {
+        let stream =
+            b"\x1b[7mThis line will be displayed inversed.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn combined_modifier_text() {
+        let stream =
+            b"\x1b[4m\x1b[3mThis line will be displayed in italic and underlined.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn dim_crossed_out_text() {
+        let stream = b"\x1b[2;9mThis line will be displayed dim and crossed out.\x1b[0m This should have no style.";
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+
+    #[test]
+    fn vttest_02() {
+        let streams: [&[u8]; 15] = [
+            include_bytes!("../test/typescript/vttest_02_01.typescript"),
+            include_bytes!("../test/typescript/vttest_02_02.typescript"),
+            include_bytes!("../test/typescript/vttest_02_03.typescript"),
+            include_bytes!("../test/typescript/vttest_02_04.typescript"),
+            include_bytes!("../test/typescript/vttest_02_05.typescript"),
+            include_bytes!("../test/typescript/vttest_02_06.typescript"),
+            include_bytes!("../test/typescript/vttest_02_07.typescript"),
+            include_bytes!("../test/typescript/vttest_02_08.typescript"),
+            include_bytes!("../test/typescript/vttest_02_09.typescript"),
+            include_bytes!("../test/typescript/vttest_02_10.typescript"),
+            include_bytes!("../test/typescript/vttest_02_11.typescript"),
+            include_bytes!("../test/typescript/vttest_02_12.typescript"),
+            include_bytes!("../test/typescript/vttest_02_13.typescript"),
+            include_bytes!("../test/typescript/vttest_02_14.typescript"),
+            include_bytes!("../test/typescript/vttest_02_15.typescript"),
+        ];
+        for (index, stream) in (1..).zip(streams) {
+            let name = format!("vttest_02_{index:02}");
+            insta::assert_snapshot!(name, snapshot_typescript(stream));
+        }
+    }
+}
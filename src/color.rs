@@ -0,0 +1,579 @@
+//! Color handling for the colors reported by a [`Screen`].
+//!
+//! A [`Palette`] remaps the indexed colors and the default foreground and background colors
+//! of the terminal to concrete colors, so that child programs look the same regardless of
+//! the palette of the host terminal.
+//!
+//! A [`ColorDepth`] reduces colors to the ones supported by the host terminal,
+//! for hosts like the Linux console that cannot display RGB colors.
+//!
+//! [`Screen`]: crate::widget::Screen
+
+use std::{error::Error, fmt, sync::OnceLock};
+
+use ratatui::style::Color;
+
+/// The levels of the red, green and blue components of the xterm 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// A set of concrete colors for the 256 indexed colors and the default colors of a terminal.
+///
+/// # Example
+///
+/// ```
+/// use ratatui::style::Color;
+/// use tui_term::{color::Palette, widget::PseudoTerminal};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// let palette = Palette::solarized_dark().with_color(1, Color::Rgb(255, 0, 0));
+/// let pseudo_term = PseudoTerminal::new(parser.screen()).palette(palette);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: [Color; 256],
+    foreground: Color,
+    background: Color,
+}
+
+impl Default for Palette {
+    /// Returns the [`Palette::xterm`] palette.
+    #[inline]
+    fn default() -> Self {
+        Self::xterm()
+    }
+}
+
+impl Palette {
+    /// The default colors of xterm.
+    ///
+    /// The default foreground and background colors are left to the host terminal.
+    #[must_use]
+    pub fn xterm() -> Self {
+        Self::from_ansi(
+            [
+                0x000000, 0xcd0000, 0x00cd00, 0xcdcd00, 0x0000ee, 0xcd00cd, 0x00cdcd, 0xe5e5e5,
+                0x7f7f7f, 0xff0000, 0x00ff00, 0xffff00, 0x5c5cff, 0xff00ff, 0x00ffff, 0xffffff,
+            ],
+            Color::Reset,
+            Color::Reset,
+        )
+    }
+
+    /// The dark variant of the [Solarized](https://ethanschoonover.com/solarized/) theme.
+    #[must_use]
+    pub fn solarized_dark() -> Self {
+        Self::from_ansi(
+            SOLARIZED,
+            Color::from_u32(0x839496),
+            Color::from_u32(0x002b36),
+        )
+    }
+
+    /// The light variant of the [Solarized](https://ethanschoonover.com/solarized/) theme.
+    #[must_use]
+    pub fn solarized_light() -> Self {
+        Self::from_ansi(
+            SOLARIZED,
+            Color::from_u32(0x657b83),
+            Color::from_u32(0xfdf6e3),
+        )
+    }
+
+    /// The dark variant of the [gruvbox](https://github.com/morhetz/gruvbox) theme.
+    #[must_use]
+    pub fn gruvbox_dark() -> Self {
+        Self::from_ansi(
+            [
+                0x282828, 0xcc241d, 0x98971a, 0xd79921, 0x458588, 0xb16286, 0x689d6a, 0xa89984,
+                0x928374, 0xfb4934, 0xb8bb26, 0xfabd2f, 0x83a598, 0xd3869b, 0x8ec07c, 0xebdbb2,
+            ],
+            Color::from_u32(0xebdbb2),
+            Color::from_u32(0x282828),
+        )
+    }
+
+    /// Creates a palette from the 16 ANSI colors,
+    /// filling the remaining indexed colors with the xterm color cube and grayscale ramp.
+    fn from_ansi(ansi: [u32; 16], foreground: Color, background: Color) -> Self {
+        let mut colors = [Color::Reset; 256];
+        for (color, rgb) in colors.iter_mut().zip(ansi) {
+            *color = Color::from_u32(rgb);
+        }
+        for (i, color) in colors.iter_mut().enumerate().skip(16) {
+            *color = xterm_color(i as u8);
+        }
+        Self {
+            colors,
+            foreground,
+            background,
+        }
+    }
+
+    /// Parses a palette from the color definitions of a [kitty](https://sw.kovidgoyal.net/kitty/conf/)
+    /// configuration or theme file.
+    ///
+    /// Lines look like `color1 #dc322f`, `foreground #839496` or `background #002b36`.
+    /// Colors that are not defined keep their [`Palette::xterm`] value,
+    /// other settings and comments are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a color definition has an invalid color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::Color;
+    /// use tui_term::color::Palette;
+    ///
+    /// let palette = Palette::from_kitty("background #002b36\ncolor1 #dc322f").unwrap();
+    /// assert_eq!(palette.background(), Color::Rgb(0x00, 0x2b, 0x36));
+    /// assert_eq!(palette.color(1), Color::Rgb(0xdc, 0x32, 0x2f));
+    /// ```
+    pub fn from_kitty(config: &str) -> Result<Self, ParsePaletteError> {
+        Self::parse(config, |line| {
+            if line.starts_with('#') {
+                return None;
+            }
+            line.split_once(char::is_whitespace)
+        })
+    }
+
+    /// Parses a palette from the color definitions of an `Xresources` file.
+    ///
+    /// Lines look like `*.color1: #dc322f`, `URxvt.foreground: #839496` or
+    /// `*background: rgb:00/2b/36`.
+    /// Colors that are not defined keep their [`Palette::xterm`] value,
+    /// other resources and comments are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a color resource has an invalid color.
+    pub fn from_xresources(resources: &str) -> Result<Self, ParsePaletteError> {
+        Self::parse(resources, |line| {
+            if line.starts_with('!') {
+                return None;
+            }
+            let (resource, value) = line.split_once(':')?;
+            let name = resource.rsplit(['.', '*']).next()?;
+            Some((name, value))
+        })
+    }
+
+    /// Parses lines of `name value` pairs, as returned by `split`.
+    fn parse(
+        config: &str,
+        split: impl Fn(&str) -> Option<(&str, &str)>,
+    ) -> Result<Self, ParsePaletteError> {
+        let mut palette = Self::xterm();
+        for (index, line) in config.lines().enumerate() {
+            let Some((name, value)) = split(line.trim()) else {
+                continue;
+            };
+            let slot = match name.trim() {
+                "foreground" => &mut palette.foreground,
+                "background" => &mut palette.background,
+                name => match name
+                    .strip_prefix("color")
+                    .and_then(|i| i.parse::<u8>().ok())
+                {
+                    Some(i) => &mut palette.colors[usize::from(i)],
+                    None => continue,
+                },
+            };
+            *slot = parse_color(value.trim()).ok_or(ParsePaletteError { line: index + 1 })?;
+        }
+        Ok(palette)
+    }
+
+    /// Returns the color of the given index.
+    #[inline]
+    #[must_use]
+    pub const fn color(&self, index: u8) -> Color {
+        self.colors[index as usize]
+    }
+
+    /// Sets the color of the given index.
+    #[inline]
+    #[must_use]
+    pub const fn with_color(mut self, index: u8, color: Color) -> Self {
+        self.colors[index as usize] = color;
+        self
+    }
+
+    /// Returns the default foreground color.
+    ///
+    /// [`Color::Reset`] leaves the default foreground color to the host terminal.
+    #[inline]
+    #[must_use]
+    pub const fn foreground(&self) -> Color {
+        self.foreground
+    }
+
+    /// Sets the default foreground color.
+    #[inline]
+    #[must_use]
+    pub const fn with_foreground(mut self, color: Color) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    /// Returns the default background color.
+    ///
+    /// [`Color::Reset`] leaves the default background color to the host terminal.
+    #[inline]
+    #[must_use]
+    pub const fn background(&self) -> Color {
+        self.background
+    }
+
+    /// Sets the default background color.
+    #[inline]
+    #[must_use]
+    pub const fn with_background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Maps a foreground color of a cell to the color of the palette.
+    ///
+    /// Indexed and named colors are looked up in the palette,
+    /// [`Color::Reset`] becomes the default foreground color and RGB colors are kept.
+    #[inline]
+    #[must_use]
+    pub const fn foreground_color(&self, color: Color) -> Color {
+        match color {
+            Color::Reset => self.foreground,
+            color => self.map(color),
+        }
+    }
+
+    /// Maps a background color of a cell to the color of the palette.
+    ///
+    /// Indexed and named colors are looked up in the palette,
+    /// [`Color::Reset`] becomes the default background color and RGB colors are kept.
+    #[inline]
+    #[must_use]
+    pub const fn background_color(&self, color: Color) -> Color {
+        match color {
+            Color::Reset => self.background,
+            color => self.map(color),
+        }
+    }
+
+    const fn map(&self, color: Color) -> Color {
+        match ansi_index(color) {
+            Some(index) => self.color(index),
+            None => color,
+        }
+    }
+}
+
+/// The colors a host terminal is able to display.
+///
+/// # Example
+///
+/// ```
+/// use ratatui::style::Color;
+/// use tui_term::color::ColorDepth;
+///
+/// assert_eq!(
+///     ColorDepth::Ansi256.convert(Color::Rgb(255, 0, 0)),
+///     Color::Indexed(196)
+/// );
+/// assert_eq!(
+///     ColorDepth::Ansi16.convert(Color::Rgb(250, 5, 5)),
+///     Color::LightRed
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// All colors are displayed as they are.
+    #[default]
+    TrueColor,
+    /// RGB colors are reduced to the 256 indexed colors.
+    Ansi256,
+    /// RGB and indexed colors are reduced to the 16 ANSI colors.
+    Ansi16,
+    /// Colors are not displayed at all.
+    ///
+    /// The highlights of the selection, the search and the cursor are drawn reversed instead,
+    /// if their styles rely on colors.
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// Converts the color to the closest color of this depth.
+    ///
+    /// The closest color is the one with the smallest perceptual distance in the
+    /// CIELAB color space, based on the [`Palette::xterm`] colors.
+    /// [`Color::Reset`] is kept as it is.
+    #[must_use]
+    pub fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, color) | (_, color @ Color::Reset) => color,
+            (Self::Monochrome, _) => Color::Reset,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest(r, g, b, 16..=255)),
+            (Self::Ansi256, color) => color,
+            (Self::Ansi16, color) => {
+                let index = match (ansi_index(color), color) {
+                    (Some(index @ 0..=15), _) => index,
+                    (Some(index), _) => match xterm_color(index) {
+                        Color::Rgb(r, g, b) => nearest(r, g, b, 0..=15),
+                        _ => unreachable!("the color cube and grayscale ramp are RGB colors"),
+                    },
+                    (None, Color::Rgb(r, g, b)) => nearest(r, g, b, 0..=15),
+                    (None, color) => return color,
+                };
+                ANSI_COLORS[usize::from(index)]
+            }
+        }
+    }
+}
+
+/// The named colors in the order of their palette index.
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Returns the index of the xterm color in `indices` that is perceptually closest to the
+/// RGB color.
+fn nearest(r: u8, g: u8, b: u8, indices: std::ops::RangeInclusive<u8>) -> u8 {
+    static XTERM_LAB: OnceLock<Vec<[f32; 3]>> = OnceLock::new();
+    let xterm_lab = XTERM_LAB.get_or_init(|| {
+        let palette = Palette::xterm();
+        (0..=255)
+            .map(|index| match palette.color(index) {
+                Color::Rgb(r, g, b) => lab(r, g, b),
+                _ => unreachable!("the xterm palette only has RGB colors"),
+            })
+            .collect()
+    });
+    let target = lab(r, g, b);
+    let distance = |index: &u8| {
+        let color = xterm_lab[usize::from(*index)];
+        (0..3).map(|i| (color[i] - target[i]).powi(2)).sum::<f32>()
+    };
+    indices
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or_default()
+}
+
+/// Converts an sRGB color into the CIELAB color space, using the D65 white point.
+fn lab(r: u8, g: u8, b: u8) -> [f32; 3] {
+    let linear = |c: u8| {
+        let c = f32::from(c) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+    let f = |t: f32| {
+        const DELTA: f32 = 6.0 / 29.0;
+        if t > DELTA.powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA.powi(2)) + 4.0 / 29.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Returns the palette index of indexed and named colors.
+pub(crate) const fn ansi_index(color: Color) -> Option<u8> {
+    let index = match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+        Color::Reset | Color::Rgb(..) => return None,
+    };
+    Some(index)
+}
+
+/// Returns the xterm color of an index of the color cube or the grayscale ramp.
+fn xterm_color(index: u8) -> Color {
+    if index >= 232 {
+        let level = 8 + 10 * (index - 232);
+        Color::Rgb(level, level, level)
+    } else {
+        let index = usize::from(index - 16);
+        Color::Rgb(
+            CUBE_LEVELS[index / 36],
+            CUBE_LEVELS[index / 6 % 6],
+            CUBE_LEVELS[index % 6],
+        )
+    }
+}
+
+/// Parses `#rrggbb`, `#rgb` and `rgb:rr/gg/bb` colors.
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    if let Some(rgb) = value.strip_prefix("rgb:") {
+        let mut parts = rgb.split('/').map(|part| part.get(..2).and_then(hex));
+        let color = Color::Rgb(parts.next()??, parts.next()??, parts.next()??);
+        return parts.next().is_none().then_some(color);
+    }
+    let digits = value.strip_prefix('#')?;
+    match digits.len() {
+        6 => Some(Color::Rgb(
+            hex(&digits[0..2])?,
+            hex(&digits[2..4])?,
+            hex(&digits[4..6])?,
+        )),
+        3 => Some(Color::Rgb(
+            hex(&digits[0..1])? * 17,
+            hex(&digits[1..2])? * 17,
+            hex(&digits[2..3])? * 17,
+        )),
+        _ => None,
+    }
+}
+
+/// The 16 ANSI colors shared by both variants of Solarized.
+const SOLARIZED: [u32; 16] = [
+    0x073642, 0xdc322f, 0x859900, 0xb58900, 0x268bd2, 0xd33682, 0x2aa198, 0xeee8d5, 0x002b36,
+    0xcb4b16, 0x586e75, 0x657b83, 0x839496, 0x6c71c4, 0x93a1a1, 0xfdf6e3,
+];
+
+/// An error that occurs when a [`Palette`] cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePaletteError {
+    line: usize,
+}
+
+impl ParsePaletteError {
+    /// Returns the line number of the invalid color definition, starting at `1`.
+    #[inline]
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for ParsePaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid color definition on line {}", self.line)
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xterm_cube_and_grayscale() {
+        let palette = Palette::xterm();
+        assert_eq!(palette.color(16), Color::Rgb(0, 0, 0));
+        assert_eq!(palette.color(196), Color::Rgb(255, 0, 0));
+        assert_eq!(palette.color(110), Color::Rgb(135, 175, 215));
+        assert_eq!(palette.color(232), Color::Rgb(8, 8, 8));
+        assert_eq!(palette.color(255), Color::Rgb(238, 238, 238));
+    }
+
+    #[test]
+    fn map_colors() {
+        let palette = Palette::gruvbox_dark();
+        assert_eq!(palette.foreground_color(Color::Reset), palette.foreground());
+        assert_eq!(palette.background_color(Color::Reset), palette.background());
+        assert_eq!(palette.foreground_color(Color::Red), palette.color(1));
+        assert_eq!(
+            palette.background_color(Color::Indexed(9)),
+            palette.color(9)
+        );
+        assert_eq!(
+            palette.foreground_color(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn color_depth() {
+        let rgb = Color::Rgb(128, 128, 128);
+        assert_eq!(ColorDepth::TrueColor.convert(rgb), rgb);
+        assert_eq!(ColorDepth::Ansi256.convert(rgb), Color::Indexed(244));
+        assert_eq!(ColorDepth::Ansi256.convert(Color::Red), Color::Red);
+        assert_eq!(ColorDepth::Ansi16.convert(rgb), Color::DarkGray);
+        assert_eq!(ColorDepth::Ansi16.convert(Color::Indexed(3)), Color::Yellow);
+        assert_eq!(
+            ColorDepth::Ansi16.convert(Color::Indexed(196)),
+            Color::LightRed
+        );
+        assert_eq!(ColorDepth::Ansi16.convert(Color::Indexed(16)), Color::Black);
+        assert_eq!(
+            ColorDepth::Ansi16.convert(Color::Indexed(231)),
+            Color::White
+        );
+        assert_eq!(ColorDepth::Monochrome.convert(Color::Blue), Color::Reset);
+        for depth in [
+            ColorDepth::TrueColor,
+            ColorDepth::Ansi256,
+            ColorDepth::Ansi16,
+            ColorDepth::Monochrome,
+        ] {
+            assert_eq!(depth.convert(Color::Reset), Color::Reset);
+        }
+    }
+
+    #[test]
+    fn parse_kitty() {
+        let config =
+            "# a comment\nfont_size 12\nforeground   #abc\ncolor255 #010203\ncolor256 #ffffff";
+        let palette = Palette::from_kitty(config).unwrap();
+        assert_eq!(palette.foreground(), Color::Rgb(0xaa, 0xbb, 0xcc));
+        assert_eq!(palette.background(), Color::Reset);
+        assert_eq!(palette.color(255), Color::Rgb(1, 2, 3));
+        assert_eq!(Palette::from_kitty("color1 red").unwrap_err().line(), 1);
+    }
+
+    #[test]
+    fn parse_xresources() {
+        let resources =
+            "! a comment\n*.color4: #268bd2\nURxvt.background: rgb:00/2b/36\n*font: mono";
+        let palette = Palette::from_xresources(resources).unwrap();
+        assert_eq!(palette.color(4), Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(palette.background(), Color::Rgb(0x00, 0x2b, 0x36));
+        assert_eq!(
+            Palette::from_xresources("\n*color0: #12345")
+                .unwrap_err()
+                .line(),
+            2
+        );
+    }
+}
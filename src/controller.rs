@@ -20,6 +20,8 @@ use std::{
 use portable_pty::{CommandBuilder, ExitStatus, PtySystem};
 use vt100::{Parser, Screen};
 
+use crate::snapshot::Snapshot;
+
 /// Controller, in charge of command dispatch
 pub struct Controller {
     // Needs to be set
@@ -96,6 +98,12 @@ impl Controller {
         }
     }
 
+    /// Returns a [`Snapshot`] of the screen, without cloning the whole `vt100::Screen`.
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        let parser = self.parser.as_ref()?.read().ok()?;
+        Some(Snapshot::new(parser.screen()))
+    }
+
     /// Whether the command finished running
     pub fn finished(&self) -> bool {
         self.exit_status.is_some()
@@ -0,0 +1,246 @@
+//! Comparing two [`Screen`]s.
+//!
+//! [`diff`] returns the cells that changed between two screens, how the cursor moved and
+//! which modes changed. The screens may come from different backends, which allows comparing
+//! a [`Snapshot`] taken earlier with the live screen, for example to send only the changes
+//! to a remote client or to wait until the output stops changing.
+//!
+//! [`Snapshot`]: crate::snapshot::Snapshot
+
+use crate::{
+    state::screen_size,
+    widget::{Cell, CursorShape, MouseProtocolEncoding, MouseProtocolMode, Screen},
+};
+
+/// A change of the state of a [`Screen`], holding the new value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ModeChange {
+    /// The size of the screen changed, as (rows, columns).
+    Size((u16, u16)),
+    /// The window title changed.
+    Title(String),
+    /// The icon name changed.
+    IconName(String),
+    /// The cursor was hidden or shown.
+    HideCursor(bool),
+    /// The shape of the cursor changed.
+    CursorShape(Option<CursorShape>),
+    /// The alternate screen was entered or left.
+    AlternateScreen(bool),
+    /// The application cursor mode changed.
+    ApplicationCursor(bool),
+    /// The application keypad mode changed.
+    ApplicationKeypad(bool),
+    /// Bracketed paste was enabled or disabled.
+    BracketedPaste(bool),
+    /// The reported mouse events changed.
+    MouseProtocolMode(MouseProtocolMode),
+    /// The encoding of reported mouse events changed.
+    MouseProtocolEncoding(MouseProtocolEncoding),
+}
+
+/// The differences between two [`Screen`]s, as returned by [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ScreenDiff {
+    cells: Vec<(u16, u16)>,
+    cursor: Option<((u16, u16), (u16, u16))>,
+    modes: Vec<ModeChange>,
+}
+
+impl ScreenDiff {
+    /// Returns whether the screens are the same.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.cursor.is_none() && self.modes.is_empty()
+    }
+
+    /// Returns the (row, column) of every cell whose contents or style changed,
+    /// ordered by row and column.
+    ///
+    /// When the size changed, the cells that exist on only one of the screens are included.
+    #[inline]
+    #[must_use]
+    pub fn changed_cells(&self) -> &[(u16, u16)] {
+        &self.cells
+    }
+
+    /// Returns the rows that contain changed cells, in ascending order.
+    #[must_use]
+    pub fn changed_rows(&self) -> Vec<u16> {
+        let mut rows: Vec<u16> = self.cells.iter().map(|&(row, _)| row).collect();
+        rows.dedup();
+        rows
+    }
+
+    /// Returns the old and the new (row, column) of the cursor, if it moved.
+    #[inline]
+    #[must_use]
+    pub const fn cursor_moved(&self) -> Option<((u16, u16), (u16, u16))> {
+        self.cursor
+    }
+
+    /// Returns the changes of the modes and of the other state of the screen.
+    #[inline]
+    #[must_use]
+    pub fn mode_changes(&self) -> &[ModeChange] {
+        &self.modes
+    }
+}
+
+/// Compares two screens.
+///
+/// # Example
+///
+/// Waiting until the output of a command stops changing:
+///
+/// ```
+/// use tui_term::{diff::diff, snapshot::Snapshot};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// # let mut output = vec![&b"building"[..], b"\r\ndone"].into_iter();
+/// let mut previous = Snapshot::new(parser.screen());
+/// loop {
+///     // read the output of the command into the parser
+///     # parser.process(output.next().unwrap_or_default());
+///     let changes = diff(&previous, parser.screen());
+///     if changes.is_empty() {
+///         break;
+///     }
+///     previous = Snapshot::new(parser.screen());
+/// }
+/// ```
+#[must_use]
+pub fn diff<A: Screen, B: Screen>(old: &A, new: &B) -> ScreenDiff {
+    let old_size = screen_size(old);
+    let new_size = screen_size(new);
+    let mut cells = Vec::new();
+    for row in 0..old_size.0.max(new_size.0) {
+        for col in 0..old_size.1.max(new_size.1) {
+            let same = match (old.cell(row, col), new.cell(row, col)) {
+                (Some(old), Some(new)) => same_cell(old, new),
+                (None, None) => true,
+                _ => false,
+            };
+            if !same {
+                cells.push((row, col));
+            }
+        }
+    }
+    let cursor =
+        Some((old.cursor_position(), new.cursor_position())).filter(|(old, new)| old != new);
+    let mut modes = Vec::new();
+    let mut compare = |old, new: ModeChange| {
+        if old != new {
+            modes.push(new);
+        }
+    };
+    compare(ModeChange::Size(old_size), ModeChange::Size(new_size));
+    compare(
+        ModeChange::Title(old.title().into()),
+        ModeChange::Title(new.title().into()),
+    );
+    compare(
+        ModeChange::IconName(old.icon_name().into()),
+        ModeChange::IconName(new.icon_name().into()),
+    );
+    compare(
+        ModeChange::HideCursor(old.hide_cursor()),
+        ModeChange::HideCursor(new.hide_cursor()),
+    );
+    compare(
+        ModeChange::CursorShape(old.cursor_shape()),
+        ModeChange::CursorShape(new.cursor_shape()),
+    );
+    compare(
+        ModeChange::AlternateScreen(old.alternate_screen()),
+        ModeChange::AlternateScreen(new.alternate_screen()),
+    );
+    compare(
+        ModeChange::ApplicationCursor(old.application_cursor()),
+        ModeChange::ApplicationCursor(new.application_cursor()),
+    );
+    compare(
+        ModeChange::ApplicationKeypad(old.application_keypad()),
+        ModeChange::ApplicationKeypad(new.application_keypad()),
+    );
+    compare(
+        ModeChange::BracketedPaste(old.bracketed_paste()),
+        ModeChange::BracketedPaste(new.bracketed_paste()),
+    );
+    compare(
+        ModeChange::MouseProtocolMode(old.mouse_protocol_mode()),
+        ModeChange::MouseProtocolMode(new.mouse_protocol_mode()),
+    );
+    compare(
+        ModeChange::MouseProtocolEncoding(old.mouse_protocol_encoding()),
+        ModeChange::MouseProtocolEncoding(new.mouse_protocol_encoding()),
+    );
+    ScreenDiff {
+        cells,
+        cursor,
+        modes,
+    }
+}
+
+/// Returns whether two cells have the same contents and style.
+fn same_cell<A: Cell, B: Cell>(old: &A, new: &B) -> bool {
+    old.has_contents() == new.has_contents()
+        && old.symbol() == new.symbol()
+        && old.fg() == new.fg()
+        && old.bg() == new.bg()
+        && old.modifiers() == new.modifiers()
+        && old.is_wide() == new.is_wide()
+        && old.is_wide_continuation() == new.is_wide_continuation()
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+    use crate::snapshot::Snapshot;
+
+    #[test]
+    fn unchanged() {
+        let mut parser = vt100::Parser::new(4, 10, 0);
+        parser.process(b"hello");
+        let snapshot = Snapshot::new(parser.screen());
+        let changes = diff(&snapshot, parser.screen());
+        assert!(changes.is_empty());
+        assert_eq!(changes, ScreenDiff::default());
+    }
+
+    #[test]
+    fn changed_cells_and_cursor() {
+        let mut parser = vt100::Parser::new(4, 10, 0);
+        parser.process(b"hello\r\nworld");
+        let snapshot = Snapshot::new(parser.screen());
+        parser.process(b"\x1b[1;2Ha\x1b[3;1H\x1b[31mx\x1b[2;1H\x1b[32mw");
+        let changes = diff(&snapshot, parser.screen());
+        assert_eq!(changes.changed_cells(), &[(0, 1), (1, 0), (2, 0)]);
+        assert_eq!(changes.changed_rows(), vec![0, 1, 2]);
+        assert_eq!(changes.cursor_moved(), Some(((1, 5), (1, 1))));
+        assert!(changes.mode_changes().is_empty());
+    }
+
+    #[test]
+    fn mode_changes() {
+        let mut parser = vt100::Parser::new(4, 10, 0);
+        let snapshot = Snapshot::new(parser.screen());
+        parser.process(b"\x1b]2;title\x07\x1b[?25l\x1b[?2004h\x1b[?1002h");
+        parser.set_size(5, 10);
+        let changes = diff(&snapshot, parser.screen());
+        assert_eq!(
+            changes.mode_changes(),
+            &[
+                ModeChange::Size((5, 10)),
+                ModeChange::Title("title".into()),
+                ModeChange::HideCursor(true),
+                ModeChange::BracketedPaste(true),
+                ModeChange::MouseProtocolMode(MouseProtocolMode::ButtonMotion),
+            ]
+        );
+        assert_eq!(changes.changed_rows(), vec![4]);
+        assert_eq!(changes.cursor_moved(), None);
+    }
+}
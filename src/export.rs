@@ -0,0 +1,304 @@
+//! Converting the contents of a [`Screen`] for use outside of the [`PseudoTerminal`].
+//!
+//! [`to_text`] turns the screen into a ratatui [`Text`], so captured output can be shown
+//! with widgets like `Paragraph`, `List` or `Table`.
+//!
+//! [`Html`] turns the screen into a standalone HTML document, for example to publish
+//! captured sessions in reports.
+//!
+//! [`Svg`] turns the screen, or a widget like the [`PseudoTerminal`], into an SVG image, for
+//! example to generate screenshots for documentation.
+//!
+//! [`to_ansi`] turns the screen into an ANSI escape stream, to replay it into another
+//! terminal.
+//!
+//! [`PseudoTerminal`]: crate::widget::PseudoTerminal
+
+use std::ops::Range;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+use crate::{
+    color::Palette,
+    state::screen_size,
+    widget::{Cell, Screen},
+};
+
+mod ansi;
+mod html;
+mod svg;
+
+pub use ansi::to_ansi;
+pub use html::Html;
+pub use svg::Svg;
+
+/// Converts the rows of the screen into a [`Text`].
+///
+/// See [`rows_to_text`] for how the rows are converted.
+///
+/// # Example
+///
+/// ```
+/// use ratatui::widgets::Paragraph;
+/// use tui_term::export::to_text;
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"\x1b[32mok\x1b[0m build finished");
+/// let paragraph = Paragraph::new(to_text(parser.screen()));
+/// ```
+#[inline]
+#[must_use]
+pub fn to_text<S: Screen>(screen: &S) -> Text<'static> {
+    rows_to_text(screen, 0..u16::MAX)
+}
+
+/// Converts the given rows of the screen into a [`Text`].
+///
+/// Every row becomes a [`Line`] with one [`Span`] per run of cells with the same style.
+/// Default colors are left unset, so the spans take the colors of the widget they are shown
+/// in. Trailing blank cells of each row and trailing empty rows are trimmed.
+///
+/// # Example
+///
+/// ```
+/// use tui_term::export::rows_to_text;
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"first\r\nsecond\r\nthird");
+/// let text = rows_to_text(parser.screen(), 1..3);
+/// assert_eq!(text.lines.len(), 2);
+/// ```
+#[must_use]
+pub fn rows_to_text<S: Screen>(screen: &S, rows: Range<u16>) -> Text<'static> {
+    let (screen_rows, cols) = screen_size(screen);
+    let lines = (rows.start..rows.end.min(screen_rows))
+        .map(|row| runs((0..cols).filter_map(|col| screen.cell(row, col))))
+        .collect();
+    Text::from(
+        trim_lines(lines)
+            .into_iter()
+            .map(|runs| {
+                Line::from(
+                    runs.into_iter()
+                        .map(|(content, style)| Span::styled(content, style))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// A run of cells with the same style.
+type Run = (String, Style);
+
+/// Returns the runs of the rows of the screen, preceded by the rows of the scrollback
+/// history if `scrollback` is set, with trailing empty rows trimmed.
+fn screen_runs<S: Screen>(screen: &S, scrollback: bool) -> Vec<Vec<Run>> {
+    let (rows, cols) = screen_size(screen);
+    let history = if scrollback {
+        screen.scrollback_len()
+    } else {
+        0
+    };
+    let mut lines: Vec<Vec<Run>> = (0..history)
+        .rev()
+        .map(|row| runs((0..cols).filter_map(|col| screen.scrollback_cell(row, col))))
+        .collect();
+    lines.extend((0..rows).map(|row| runs((0..cols).filter_map(|col| screen.cell(row, col)))));
+    trim_lines(lines)
+}
+
+/// Returns the runs of cells with the same style, with trailing blank cells trimmed.
+fn runs<'a, C: Cell + 'a>(cells: impl Iterator<Item = &'a C>) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for cell in cells.filter(|cell| !cell.is_wide_continuation()) {
+        let style = cell_style(cell);
+        match runs.last_mut() {
+            Some((content, run_style)) if *run_style == style => content.push_str(&cell.symbol()),
+            _ => runs.push((cell.symbol().into_owned(), style)),
+        }
+    }
+    while let Some((content, style)) = runs.last_mut() {
+        if !is_blank_style(*style) {
+            break;
+        }
+        let trimmed = content.trim_end().len();
+        content.truncate(trimmed);
+        if !content.is_empty() {
+            break;
+        }
+        runs.pop();
+    }
+    runs
+}
+
+/// Removes the trailing empty lines.
+fn trim_lines(mut lines: Vec<Vec<Run>>) -> Vec<Vec<Run>> {
+    while lines.last().is_some_and(Vec::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Returns the style of a cell, leaving the default colors unset.
+fn cell_style<C: Cell>(cell: &C) -> Style {
+    let mut style = Style::default().add_modifier(cell.modifiers());
+    if cell.fg() != Color::Reset {
+        style = style.fg(cell.fg());
+    }
+    if cell.bg() != Color::Reset {
+        style = style.bg(cell.bg());
+    }
+    style
+}
+
+/// Returns whether whitespace in this style is invisible.
+fn is_blank_style(style: Style) -> bool {
+    style.bg.is_none()
+        && !style
+            .add_modifier
+            .intersects(Modifier::REVERSED | Modifier::UNDERLINED | Modifier::CROSSED_OUT)
+}
+
+/// A color as (red, green, blue).
+type Rgb = (u8, u8, u8);
+
+/// Returns the foreground and background color of a style, resolved with the palette.
+///
+/// Default colors that the palette leaves to the host terminal become the colors returned by
+/// [`default_colors`]. Reversed, dim and hidden text is taken into account.
+fn resolve(palette: &Palette, style: Style) -> (Rgb, Rgb) {
+    let (default_fg, default_bg) = default_colors(palette);
+    let fg = style
+        .fg
+        .and_then(|color| rgb(palette, color))
+        .unwrap_or(default_fg);
+    let bg = style
+        .bg
+        .and_then(|color| rgb(palette, color))
+        .unwrap_or(default_bg);
+    let (fg, bg) = if style.add_modifier.contains(Modifier::REVERSED) {
+        (bg, fg)
+    } else {
+        (fg, bg)
+    };
+    let fg = if style.add_modifier.contains(Modifier::HIDDEN) {
+        bg
+    } else if style.add_modifier.contains(Modifier::DIM) {
+        let mix = |fg: u8, bg: u8| u8::try_from((u16::from(fg) + u16::from(bg)) / 2).unwrap_or(fg);
+        (mix(fg.0, bg.0), mix(fg.1, bg.1), mix(fg.2, bg.2))
+    } else {
+        fg
+    };
+    (fg, bg)
+}
+
+/// Returns the default foreground and background color of the palette.
+///
+/// When the palette leaves them to the host terminal, color 7 and color 0 are used.
+fn default_colors(palette: &Palette) -> (Rgb, Rgb) {
+    let fg = rgb(palette, palette.foreground())
+        .or_else(|| rgb(palette, palette.color(7)))
+        .unwrap_or((0xe5, 0xe5, 0xe5));
+    let bg = rgb(palette, palette.background())
+        .or_else(|| rgb(palette, palette.color(0)))
+        .unwrap_or((0x00, 0x00, 0x00));
+    (fg, bg)
+}
+
+/// Returns the RGB value of a color, looking up indexed and named colors in the palette.
+///
+/// Returns `None` for [`Color::Reset`].
+fn rgb(palette: &Palette, color: Color) -> Option<Rgb> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        // Palettes may map to named colors, which are looked up in the xterm palette
+        color => match palette.foreground_color(color) {
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            Color::Reset => None,
+            color => match Palette::xterm().foreground_color(color) {
+                Color::Rgb(r, g, b) => Some((r, g, b)),
+                _ => None,
+            },
+        },
+    }
+}
+
+/// Formats a color as `#rrggbb`.
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Escapes the characters that have a meaning in HTML and XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+
+    fn screen(stream: &[u8]) -> vt100::Screen {
+        let mut parser = vt100::Parser::new(4, 20, 0);
+        parser.process(stream);
+        parser.screen().clone()
+    }
+
+    #[test]
+    fn merges_style_runs() {
+        let screen = screen(b"plain \x1b[1;31mbold red\x1b[0m   \r\n\x1b[44mblue  \x1b[0m");
+        let text = to_text(&screen);
+        assert_eq!(
+            text,
+            Text::from(vec![
+                Line::from(vec![
+                    Span::raw("plain "),
+                    Span::styled(
+                        "bold red",
+                        Style::default()
+                            .fg(Color::Indexed(1))
+                            .add_modifier(Modifier::BOLD)
+                    ),
+                ]),
+                Line::from(vec![Span::styled(
+                    "blue  ",
+                    Style::default().bg(Color::Indexed(4))
+                )]),
+            ])
+        );
+    }
+
+    #[test]
+    fn row_range() {
+        let screen = screen(b"first\r\n\r\nthird\r\nfourth");
+        assert_eq!(
+            rows_to_text(&screen, 1..3),
+            Text::from(vec![Line::default(), Line::from("third")])
+        );
+        assert_eq!(
+            rows_to_text(&screen, 0..2),
+            Text::from(vec![Line::from("first")])
+        );
+        assert_eq!(rows_to_text(&screen, 3..10), Text::from("fourth"));
+    }
+
+    #[test]
+    fn wide_chars() {
+        let screen = screen("中文 text".as_bytes());
+        assert_eq!(to_text(&screen), Text::from("中文 text"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::io::Write;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use super::screen_runs;
+use crate::{
+    color::ansi_index,
+    widget::{CursorShape, Screen},
+};
+
+/// Converts the screen into an ANSI escape stream that reproduces it in another terminal.
+///
+/// The stream clears the terminal, draws the visible rows with their colors and attributes,
+/// and restores the position, visibility and shape of the cursor. It only depends on the
+/// [`Screen`] trait, so a screen of one backend can be replayed into any other terminal.
+/// Trailing blank cells and empty rows are left out.
+///
+/// # Example
+///
+/// ```
+/// use tui_term::export::to_ansi;
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"\x1b[31mred\x1b[0m text");
+///
+/// let mut replayed = vt100::Parser::new(24, 80, 0);
+/// replayed.process(&to_ansi(parser.screen()));
+/// assert_eq!(replayed.screen().contents(), "red text");
+/// ```
+#[must_use]
+pub fn to_ansi<S: Screen>(screen: &S) -> Vec<u8> {
+    let mut ansi = b"\x1b[0m\x1b[H\x1b[2J".to_vec();
+    let mut current = Style::default();
+    for (row, line) in (1..).zip(screen_runs(screen, false)) {
+        if line.is_empty() {
+            continue;
+        }
+        let _ = write!(ansi, "\x1b[{row};1H");
+        for (content, style) in line {
+            if style != current {
+                let _ = write!(ansi, "\x1b[{}m", sgr(style));
+                current = style;
+            }
+            ansi.extend_from_slice(content.as_bytes());
+        }
+    }
+    ansi.extend_from_slice(b"\x1b[0m");
+    let (row, col) = screen.cursor_position();
+    let _ = write!(ansi, "\x1b[{};{}H", u32::from(row) + 1, u32::from(col) + 1);
+    if let Some(shape) = screen.cursor_shape() {
+        let ps = match shape {
+            CursorShape::Block => 2,
+            CursorShape::Underline => 4,
+            CursorShape::Bar => 6,
+        };
+        let _ = write!(ansi, "\x1b[{ps} q");
+    }
+    if screen.hide_cursor() {
+        ansi.extend_from_slice(b"\x1b[?25l");
+    } else {
+        ansi.extend_from_slice(b"\x1b[?25h");
+    }
+    ansi
+}
+
+/// Returns the parameters of the SGR sequence that sets the style, starting from a reset.
+fn sgr(style: Style) -> String {
+    let mut params = vec![String::from("0")];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, param) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            params.push(param.into());
+        }
+    }
+    if let Some(fg) = style.fg {
+        params.extend(color(fg, 30, 90, 38));
+    }
+    if let Some(bg) = style.bg {
+        params.extend(color(bg, 40, 100, 48));
+    }
+    params.join(";")
+}
+
+/// Returns the SGR parameters of a color, given the base parameters of the normal colors,
+/// the bright colors and the extended colors.
+fn color(color: Color, normal: u8, bright: u8, extended: u8) -> Option<String> {
+    match (color, ansi_index(color)) {
+        (Color::Rgb(r, g, b), _) => Some(format!("{extended};2;{r};{g};{b}")),
+        (_, Some(index @ 0..=7)) => Some((normal + index).to_string()),
+        (_, Some(index @ 8..=15)) => Some((bright + index - 8).to_string()),
+        (_, Some(index)) => Some(format!("{extended};5;{index}")),
+        (_, None) => None,
+    }
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream() {
+        let mut parser = vt100::Parser::new(3, 10, 0);
+        parser.process(b"a\x1b[1;31mb\x1b[0m\r\n\r\n\x1b[38;5;200;48;2;1;2;3mc\x1b[?25l");
+        assert_eq!(
+            String::from_utf8(to_ansi(parser.screen())).unwrap(),
+            "\x1b[0m\x1b[H\x1b[2J\x1b[1;1Ha\x1b[0;1;31mb\
+             \x1b[3;1H\x1b[0;38;5;200;48;2;1;2;3mc\x1b[0m\x1b[3;2H\x1b[?25l"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut parser = vt100::Parser::new(6, 20, 0);
+        parser.process(
+            "$ ls\r\n\x1b[1;34mdir\x1b[0m  \x1b[4;7mfile\x1b[0m 中文\r\n\
+             \x1b[3;48;5;22m bg \x1b[0m\r\n\x1b[97;101mbright\x1b[0m\x1b[2;3H"
+                .as_bytes(),
+        );
+        let mut replayed = vt100::Parser::new(6, 20, 0);
+        replayed.process(b"garbage\r\n\x1b[1;44mto be cleared");
+        replayed.process(&to_ansi(parser.screen()));
+        let (screen, replayed) = (parser.screen(), replayed.screen());
+        assert_eq!(replayed.contents_formatted(), screen.contents_formatted());
+        assert_eq!(replayed.cursor_position(), screen.cursor_position());
+        assert!(!replayed.hide_cursor());
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Write;
+
+use ratatui::style::{Modifier, Style};
+
+use super::{default_colors, escape, hex, resolve, screen_runs, Rgb};
+use crate::{color::Palette, widget::Screen};
+
+/// Exports a [`Screen`] into a standalone HTML document.
+///
+/// The text is laid out in a `<pre>` element, with the colors and attributes of the cells as
+/// inline CSS. Colors are resolved with the [`Palette`], which defaults to
+/// [`Palette::xterm`]. Blinking text is exported as regular text.
+///
+/// # Example
+///
+/// ```
+/// use tui_term::{color::Palette, export::Html};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 1000);
+/// parser.process(b"\x1b[1;32mPASS\x1b[0m all tests");
+/// let html = Html::new()
+///     .palette(Palette::solarized_dark())
+///     .scrollback(true)
+///     .title("CI build")
+///     .export(parser.screen());
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Html {
+    palette: Palette,
+    scrollback: bool,
+    title: Option<String>,
+}
+
+impl Html {
+    /// Creates a new `Html` exporter.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the palette that the colors are resolved with.
+    #[inline]
+    #[must_use]
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets whether the scrollback history is exported above the screen.
+    ///
+    /// Only backends that implement [`Screen::scrollback_cell`] have a history to export.
+    #[inline]
+    #[must_use]
+    pub const fn scrollback(mut self, scrollback: bool) -> Self {
+        self.scrollback = scrollback;
+        self
+    }
+
+    /// Sets the title of the document.
+    ///
+    /// Defaults to the title of the screen, or `Terminal` if the screen has no title.
+    #[inline]
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Exports the screen into an HTML document.
+    #[must_use]
+    pub fn export<S: Screen>(&self, screen: &S) -> String {
+        let title = match self.title.as_deref() {
+            Some(title) => title,
+            None if screen.title().is_empty() => "Terminal",
+            None => screen.title(),
+        };
+        let defaults = default_colors(&self.palette);
+        let (fg, bg) = defaults;
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", escape(title));
+        html.push_str("</head>\n");
+        let _ = writeln!(html, "<body style=\"margin:0;background:{}\">", hex(bg));
+        let _ = write!(
+            html,
+            "<pre style=\"margin:0;padding:8px;color:{};background:{};\
+             font-family:monospace\">",
+            hex(fg),
+            hex(bg)
+        );
+        for (index, line) in screen_runs(screen, self.scrollback).iter().enumerate() {
+            if index > 0 {
+                html.push('\n');
+            }
+            for (content, style) in line {
+                let css = css(&self.palette, *style, defaults);
+                if css.is_empty() {
+                    html.push_str(&escape(content));
+                } else {
+                    let _ = write!(html, "<span style=\"{css}\">{}</span>", escape(content));
+                }
+            }
+        }
+        html.push_str("</pre>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// Returns the inline CSS of a style, leaving out the properties of the document.
+fn css(palette: &Palette, style: Style, defaults: (Rgb, Rgb)) -> String {
+    let (fg, bg) = resolve(palette, style);
+    let mut properties = Vec::new();
+    if fg != defaults.0 {
+        properties.push(format!("color:{}", hex(fg)));
+    }
+    if bg != defaults.1 {
+        properties.push(format!("background:{}", hex(bg)));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        properties.push("font-weight:bold".into());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        properties.push("font-style:italic".into());
+    }
+    match (
+        style.add_modifier.contains(Modifier::UNDERLINED),
+        style.add_modifier.contains(Modifier::CROSSED_OUT),
+    ) {
+        (true, true) => properties.push("text-decoration:underline line-through".into()),
+        (true, false) => properties.push("text-decoration:underline".into()),
+        (false, true) => properties.push("text-decoration:line-through".into()),
+        (false, false) => {}
+    }
+    properties.join(";")
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document() {
+        let mut parser = vt100::Parser::new(4, 20, 10);
+        parser.process(b"\x1b]2;build <1>\x07");
+        parser.process(b"$ cargo test\r\n\x1b[1;32mok\x1b[0m & \x1b[4;41mfailed\x1b[0m\r\n");
+        parser.process(b"\x1b[7mreversed\x1b[0m \x1b[3mitalic\x1b[0m");
+        let html = Html::new().export(parser.screen());
+        insta::assert_snapshot!(html);
+    }
+
+    #[test]
+    fn scrollback_and_palette() {
+        let mut parser = vt100::Parser::new(2, 20, 10);
+        parser.process(b"first\r\nsecond\r\n\x1b[31mthird\x1b[0m");
+        let html = Html::new()
+            .palette(Palette::solarized_dark())
+            .scrollback(true)
+            .title("log")
+            .export(&crate::ScrollbackScreen::new(
+                &mut parser,
+                usize::MAX,
+                usize::MAX,
+            ));
+        insta::assert_snapshot!(html);
+    }
+}
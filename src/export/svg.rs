@@ -0,0 +1,294 @@
+use std::fmt::Write;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::Widget,
+};
+
+use super::{default_colors, escape, hex, resolve};
+use crate::{
+    color::Palette,
+    state::screen_size,
+    widget::{PseudoTerminal, Screen},
+};
+
+/// Exports a [`Screen`], or any widget like a [`PseudoTerminal`] with a block, into an SVG
+/// image.
+///
+/// Every cell is laid out on a fixed grid, so the image looks the same regardless of the
+/// metrics of the font that displays it. Colors are resolved with the [`Palette`], which
+/// defaults to [`Palette::xterm`].
+///
+/// # Example
+///
+/// ```
+/// use ratatui::widgets::{Block, Borders};
+/// use tui_term::{color::Palette, export::Svg, widget::PseudoTerminal};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"$ ls\r\nCargo.toml src");
+/// let svg = Svg::new()
+///     .palette(Palette::gruvbox_dark())
+///     .font("Fira Code", 16.0);
+/// let screenshot = svg.export(parser.screen());
+/// let framed = svg.export_widget(
+///     PseudoTerminal::new(parser.screen())
+///         .block(Block::default().title("shell").borders(Borders::ALL)),
+///     82,
+///     26,
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Svg {
+    palette: Palette,
+    font_family: String,
+    font_size: f32,
+    cell_width: f32,
+    cell_height: f32,
+}
+
+impl Default for Svg {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            palette: Palette::xterm(),
+            font_family: "monospace".into(),
+            font_size: 14.0,
+            cell_width: 8.4,
+            cell_height: 18.0,
+        }
+    }
+}
+
+impl Svg {
+    /// Creates a new `Svg` exporter.
+    ///
+    /// Text is set in a 14 pixel `monospace` font on cells of 8.4 by 18 pixels.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the palette that the colors are resolved with.
+    #[inline]
+    #[must_use]
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets the font family and the font size in pixels.
+    ///
+    /// The size of the cells is not changed, see [`Svg::cell_size`].
+    #[inline]
+    #[must_use]
+    pub fn font(mut self, family: &str, size: f32) -> Self {
+        self.font_family = family.into();
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the width and height of a cell in pixels.
+    #[inline]
+    #[must_use]
+    pub const fn cell_size(mut self, width: f32, height: f32) -> Self {
+        self.cell_width = width;
+        self.cell_height = height;
+        self
+    }
+
+    /// Exports the screen, as drawn by a [`PseudoTerminal`] of the size of the screen.
+    #[must_use]
+    pub fn export<S: Screen>(&self, screen: &S) -> String {
+        let (rows, cols) = screen_size(screen);
+        self.export_widget(PseudoTerminal::new(screen), cols, rows)
+    }
+
+    /// Exports a widget, rendered into an area of the given size.
+    #[must_use]
+    pub fn export_widget<W: Widget>(&self, widget: W, width: u16, height: u16) -> String {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, height));
+        widget.render(buffer.area, &mut buffer);
+        self.export_buffer(&buffer)
+    }
+
+    /// Exports the contents of a buffer.
+    #[must_use]
+    pub fn export_buffer(&self, buffer: &Buffer) -> String {
+        let area = buffer.area;
+        let (_, default_bg) = default_colors(&self.palette);
+        let width = f32::from(area.width) * self.cell_width;
+        let height = f32::from(area.height) * self.cell_height;
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"{}\" font-size=\"{}\">",
+            escape(&self.font_family),
+            num(self.font_size),
+            w = num(width),
+            h = num(height),
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            hex(default_bg)
+        );
+        let mut texts = String::new();
+        for y in 0..area.height {
+            for (x, cells, content, style) in runs(buffer, area.y + y) {
+                let (fg, bg) = resolve(&self.palette, style);
+                let x = f32::from(x - area.x) * self.cell_width;
+                let top = f32::from(y) * self.cell_height;
+                let run_width = f32::from(cells) * self.cell_width;
+                if bg != default_bg {
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        num(x),
+                        num(top),
+                        num(run_width),
+                        num(self.cell_height),
+                        hex(bg)
+                    );
+                }
+                let hidden = style.add_modifier.contains(Modifier::HIDDEN);
+                // Trailing spaces would be stretched over by the text length
+                let text = content.trim_end_matches(' ');
+                if hidden || text.trim().is_empty() {
+                    continue;
+                }
+                let spaces = u16::try_from(content.len() - text.len()).unwrap_or(cells);
+                let text_width = f32::from(cells - spaces) * self.cell_width;
+                let _ = writeln!(
+                    texts,
+                    "<text x=\"{}\" y=\"{}\" textLength=\"{}\" fill=\"{}\"{} \
+                     xml:space=\"preserve\">{}</text>",
+                    num(x),
+                    num(top + (self.cell_height + self.font_size) / 2.0 - self.font_size * 0.2),
+                    num(text_width),
+                    hex(fg),
+                    attributes(style),
+                    escape(text)
+                );
+            }
+        }
+        svg.push_str(&texts);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Returns the runs of cells with the same style in a row of the buffer,
+/// as (column, width in cells, content, style).
+///
+/// The cells covered by a wide character are added to its run without their content,
+/// like [`Buffer::diff`] skips them.
+fn runs(buffer: &Buffer, y: u16) -> Vec<(u16, u16, String, Style)> {
+    let mut runs: Vec<(u16, u16, String, Style)> = Vec::new();
+    let mut covered = 0;
+    for x in buffer.area.left()..buffer.area.right() {
+        let cell = &buffer[(x, y)];
+        if covered > 0 {
+            covered -= 1;
+            if let Some((_, cells, ..)) = runs.last_mut() {
+                *cells += 1;
+                continue;
+            }
+        }
+        covered = Span::raw(cell.symbol()).width().saturating_sub(1);
+        let style = cell.style();
+        match runs.last_mut() {
+            Some((_, cells, content, run_style)) if *run_style == style => {
+                *cells += 1;
+                content.push_str(cell.symbol());
+            }
+            _ => runs.push((x, 1, cell.symbol().into(), style)),
+        }
+    }
+    runs
+}
+
+/// Returns the SVG attributes for the text attributes of a style.
+fn attributes(style: Style) -> String {
+    let mut attributes = String::new();
+    if style.add_modifier.contains(Modifier::BOLD) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    match (
+        style.add_modifier.contains(Modifier::UNDERLINED),
+        style.add_modifier.contains(Modifier::CROSSED_OUT),
+    ) {
+        (true, true) => attributes.push_str(" text-decoration=\"underline line-through\""),
+        (true, false) => attributes.push_str(" text-decoration=\"underline\""),
+        (false, true) => attributes.push_str(" text-decoration=\"line-through\""),
+        (false, false) => {}
+    }
+    attributes
+}
+
+/// Formats a length with at most two decimals, so the output does not depend on rounding
+/// errors.
+fn num(value: f32) -> String {
+    let formatted = format!("{value:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use ratatui::widgets::{Block, Borders};
+
+    use super::*;
+
+    #[test]
+    fn screen() {
+        let stream = include_bytes!("../../test/typescript/simple_ls.typescript");
+        let mut parser = vt100::Parser::new(6, 40, 0);
+        parser.process(stream);
+        let svg = Svg::new().export(parser.screen());
+        insta::assert_snapshot!(svg);
+    }
+
+    #[test]
+    fn widget_with_block() {
+        let mut parser = vt100::Parser::new(2, 12, 0);
+        parser.process(b"\x1b[1mbold\x1b[0m <&>\r\n\x1b[3;4mstyled\x1b[0m");
+        let svg = Svg::new()
+            .palette(Palette::solarized_dark())
+            .font("Fira Code", 16.0)
+            .cell_size(10.0, 20.0)
+            .export_widget(
+                PseudoTerminal::new(parser.screen())
+                    .block(Block::default().title("sh").borders(Borders::ALL)),
+                14,
+                4,
+            );
+        insta::assert_snapshot!(svg);
+    }
+
+    #[test]
+    fn wide_chars() {
+        let mut parser = vt100::Parser::new(2, 12, 0);
+        parser.process("中文 text\r\n\x1b[1m表\x1b[0mb".as_bytes());
+        let svg = Svg::new().export(parser.screen());
+        insta::assert_snapshot!(svg);
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(num(16.8), "16.8");
+        assert_eq!(num(8.4 * 3.0), "25.2");
+        assert_eq!(num(18.0), "18");
+        assert_eq!(num(0.0), "0");
+    }
+}
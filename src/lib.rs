@@ -42,20 +42,63 @@
 //! # Features
 //!
 //! - Support for parsing and processing terminal control sequences using the `vt100` crate.
+//! - Scrolling through the history of a `vt100::Parser` with [`ScrollbackScreen`].
+//! - Searching the terminal with regular expressions, behind the `regex` feature.
+//! - A built-in backend on top of the `vte` parser, behind the `vte` feature, which supports more
+//!   text attributes than `vt100` and reflows soft-wrapped rows on resize.
+//! - Rendering an `alacritty_terminal::Term`, behind the `alacritty` feature.
+//! - Rendering a `termwiz::surface::Surface` with [`SurfaceScreen`], behind the `termwiz` feature,
+//!   which [`SurfaceParser`] feeds with the output of a pseudo-terminal, hyperlinks included.
+//! - Converting a screen into a ratatui `Text`, a standalone HTML document, an SVG image or an ANSI
+//!   escape stream with the [`export`] module.
+//! - Owned, backend-neutral snapshots of a screen with the [`snapshot`] module, which can be
+//!   serialized behind the `serde` feature.
+//! - Comparing screens and snapshots with the [`diff`] module.
+//! - Downscaled thumbnails of a terminal with the [`thumbnail::PseudoTerminalThumbnail`] widget.
 //!
 //! # Limitations
 //!
-//! - The `vt100` crate is currently the only supported backend for parsing terminal control
-//!   sequences, but future versions may introduce support for alternative backends.
+//! - Alternative backends need to implement the [`widget::Screen`] and [`widget::Cell`] traits.
+//! - The terminal emulator of wezterm, `wezterm-term`, is not published on crates.io. The `termwiz`
+//!   feature parses escape sequences with the parser of `termwiz` instead, into a surface without a
+//!   scrollback history or scroll regions.
 
+#[cfg(feature = "alacritty")]
+mod alacritty_imp;
+pub mod color;
+pub mod diff;
+pub mod export;
+pub mod search;
+pub mod selection;
+pub mod snapshot;
 mod state;
+#[cfg(feature = "termwiz")]
+mod termwiz_imp;
+#[cfg(all(
+    test,
+    any(feature = "vt100", feature = "alacritty", feature = "termwiz")
+))]
+mod testing;
+pub mod thumbnail;
 #[cfg(feature = "vt100")]
 mod vt100_imp;
+#[cfg(feature = "vte")]
+pub mod vte_backend;
 pub mod widget;
 
 #[cfg(feature = "unstable")]
 pub mod controller;
 
+/// Reexport of the alacritty_terminal crate to ensure correct version compatibility
+#[cfg(feature = "alacritty")]
+pub use alacritty_terminal;
+/// Reexport of the termwiz crate to ensure correct version compatibility
+#[cfg(feature = "termwiz")]
+pub use termwiz;
+#[cfg(feature = "termwiz")]
+pub use termwiz_imp::{SurfaceCell, SurfaceParser, SurfaceScreen};
 /// Reexport of the vt100 crate to ensure correct version compatibility
 #[cfg(feature = "vt100")]
 pub use vt100;
+#[cfg(feature = "vt100")]
+pub use vt100_imp::ScrollbackScreen;
@@ -0,0 +1,326 @@
+//! Searching for text on a [`Screen`] and its scrollback history.
+//!
+//! A [`Search`] is kept inside the [`PseudoTerminalState`] and its matches are highlighted
+//! when the [`PseudoTerminal`] is rendered as a stateful widget.
+//!
+//! Matches are located by line, where line `0` is the oldest row of the scrollback history
+//! and the rows of the screen follow after the last row of the history.
+//! Rows that are soft-wrapped are searched as one line, so matches can span multiple rows.
+//!
+//! [`PseudoTerminal`]: crate::widget::PseudoTerminal
+//! [`PseudoTerminalState`]: crate::widget::PseudoTerminalState
+
+use crate::{
+    state::{line_cell, line_wrapped, screen_size},
+    widget::{Cell, Screen},
+};
+
+/// What to search for.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SearchQuery {
+    /// Matches the exact text.
+    Literal(String),
+    /// Matches a regular expression.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl SearchQuery {
+    /// Creates a query that matches the exact text.
+    #[inline]
+    #[must_use]
+    pub fn literal(text: &str) -> Self {
+        Self::Literal(text.into())
+    }
+
+    /// Creates a query that matches the regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self::Regex)
+    }
+
+    /// Returns the byte ranges of all non-empty matches in the text.
+    fn find(&self, text: &str) -> Vec<(usize, usize)> {
+        match self {
+            Self::Literal(literal) if literal.is_empty() => Vec::new(),
+            Self::Literal(literal) => text
+                .match_indices(literal.as_str())
+                .map(|(start, m)| (start, start + m.len()))
+                .collect(),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        }
+    }
+}
+
+/// A single match of a [`Search`].
+///
+/// Both start and end are inclusive and given as (line, column).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SearchMatch {
+    start: (usize, u16),
+    end: (usize, u16),
+}
+
+impl SearchMatch {
+    /// Returns the (line, column) of the first cell of the match.
+    #[inline]
+    #[must_use]
+    pub const fn start(&self) -> (usize, u16) {
+        self.start
+    }
+
+    /// Returns the (line, column) of the last cell of the match.
+    #[inline]
+    #[must_use]
+    pub const fn end(&self) -> (usize, u16) {
+        self.end
+    }
+
+    /// Returns whether the cell at the given line and column is part of the match.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, line: usize, col: u16) -> bool {
+        self.start <= (line, col) && (line, col) <= self.end
+    }
+}
+
+/// The matches of a [`SearchQuery`] on a [`Screen`],
+/// with one of them being the current match.
+#[derive(Debug, Clone)]
+pub struct Search {
+    query: SearchQuery,
+    matches: Vec<SearchMatch>,
+    current: Option<usize>,
+}
+
+impl Search {
+    /// Searches the screen, including its scrollback history, for the query.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::search::{Search, SearchQuery};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// parser.process(b"foo bar foo");
+    /// let search = Search::new(parser.screen(), SearchQuery::literal("foo"));
+    /// assert_eq!(search.matches().len(), 2);
+    /// ```
+    #[must_use]
+    pub fn new<S: Screen>(screen: &S, query: SearchQuery) -> Self {
+        let mut search = Self {
+            query,
+            matches: Vec::new(),
+            current: None,
+        };
+        search.update(screen);
+        search
+    }
+
+    /// Runs the query again on the screen, for example after new output was processed.
+    ///
+    /// The current match is reset.
+    pub fn update<S: Screen>(&mut self, screen: &S) {
+        self.matches.clear();
+        self.current = None;
+
+        let (rows, _) = screen_size(screen);
+        let lines = screen.scrollback_len() + usize::from(rows);
+        let mut text = String::new();
+        // The byte offset in `text`, the location and the width of every character
+        let mut cells: Vec<(usize, (usize, u16), u16)> = Vec::new();
+        for line in 0..lines {
+            let mut col = 0;
+            while let Some(cell) = line_cell(screen, line, col) {
+                if cell.is_wide_continuation() {
+                    // The wide character before already covers this column
+                    if let Some((_, _, width)) = cells.last_mut() {
+                        *width += 1;
+                    }
+                } else {
+                    cells.push((text.len(), (line, col), 1));
+                    text.push_str(&cell.symbol());
+                }
+                col += 1;
+            }
+            if !line_wrapped(screen, line) || line + 1 == lines {
+                self.find_in(&text, &cells);
+                text.clear();
+                cells.clear();
+            }
+        }
+    }
+
+    /// Adds the matches of the query in the text of a single logical line.
+    fn find_in(&mut self, text: &str, cells: &[(usize, (usize, u16), u16)]) {
+        let cell_at =
+            |byte: usize| cells[cells.partition_point(|(offset, ..)| *offset <= byte) - 1];
+        for (start, end) in self.query.find(text) {
+            let (_, start, _) = cell_at(start);
+            let (_, (line, col), width) = cell_at(end - 1);
+            self.matches.push(SearchMatch {
+                start,
+                end: (line, col + width - 1),
+            });
+        }
+    }
+
+    /// Returns the query that is searched for.
+    #[inline]
+    #[must_use]
+    pub const fn query(&self) -> &SearchQuery {
+        &self.query
+    }
+
+    /// Returns all matches, ordered from the top of the history to the bottom of the screen.
+    #[inline]
+    #[must_use]
+    pub fn matches(&self) -> &[SearchMatch] {
+        &self.matches
+    }
+
+    /// Returns the current match.
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> Option<&SearchMatch> {
+        self.matches.get(self.current?)
+    }
+
+    /// Makes the match after the current one the current match and returns it,
+    /// wrapping around to the first match.
+    pub fn next_match(&mut self) -> Option<&SearchMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let next = self
+            .current
+            .map_or(0, |current| (current + 1) % self.matches.len());
+        self.current = Some(next);
+        self.matches.get(next)
+    }
+
+    /// Makes the match before the current one the current match and returns it,
+    /// wrapping around to the last match.
+    ///
+    /// Without a current match, the last match is made current,
+    /// which is the one closest to the bottom of the screen.
+    pub fn previous_match(&mut self) -> Option<&SearchMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let len = self.matches.len();
+        let previous = self
+            .current
+            .map_or(len - 1, |current| (current + len - 1) % len);
+        self.current = Some(previous);
+        self.matches.get(previous)
+    }
+
+    /// Returns whether the cell at the given line and column is part of a match,
+    /// and whether that match is the current one.
+    pub(crate) fn highlight(&self, line: usize, col: u16) -> Option<bool> {
+        // Matches are sorted and do not overlap,
+        // so only the last one that starts before the cell can contain it
+        let index = self
+            .matches
+            .partition_point(|m| m.start <= (line, col))
+            .checked_sub(1)?;
+        self.matches[index]
+            .contains(line, col)
+            .then(|| self.current == Some(index))
+    }
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+
+    fn screen(stream: &[u8]) -> vt100::Screen {
+        let mut parser = vt100::Parser::new(4, 10, 0);
+        parser.process(stream);
+        parser.screen().clone()
+    }
+
+    #[test]
+    fn literal_matches() {
+        let screen = screen(b"foo bar\r\nbarfoo");
+        let search = Search::new(&screen, SearchQuery::literal("bar"));
+        let matches: Vec<_> = search
+            .matches()
+            .iter()
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        assert_eq!(matches, [((0, 4), (0, 6)), ((1, 0), (1, 2))]);
+    }
+
+    #[test]
+    fn empty_query_has_no_matches() {
+        let screen = screen(b"foo bar");
+        let search = Search::new(&screen, SearchQuery::literal(""));
+        assert!(search.matches().is_empty());
+    }
+
+    #[test]
+    fn match_across_wrapped_rows() {
+        let screen = screen(b"0123456789abcdef");
+        let search = Search::new(&screen, SearchQuery::literal("89ab"));
+        let found = search.matches()[0];
+        assert_eq!((found.start(), found.end()), ((0, 8), (1, 1)));
+        assert_eq!(search.highlight(0, 9), Some(false));
+        assert_eq!(search.highlight(1, 2), None);
+    }
+
+    #[test]
+    fn wide_chars() {
+        let screen = screen("a中文b 中".as_bytes());
+        let search = Search::new(&screen, SearchQuery::literal("中文b"));
+        let found = search.matches()[0];
+        assert_eq!((found.start(), found.end()), ((0, 1), (0, 5)));
+        let search = Search::new(&screen, SearchQuery::literal("中"));
+        let matches: Vec<_> = search
+            .matches()
+            .iter()
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        assert_eq!(matches, [((0, 1), (0, 2)), ((0, 7), (0, 8))]);
+    }
+
+    #[test]
+    fn navigation_wraps_around() {
+        let screen = screen(b"a\r\na\r\na");
+        let mut search = Search::new(&screen, SearchQuery::literal("a"));
+        assert_eq!(search.current(), None);
+        assert_eq!(search.previous_match().unwrap().start(), (2, 0));
+        assert_eq!(search.next_match().unwrap().start(), (0, 0));
+        assert_eq!(search.previous_match().unwrap().start(), (2, 0));
+        assert_eq!(search.highlight(2, 0), Some(true));
+        assert_eq!(search.highlight(1, 0), Some(false));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_matches() {
+        let screen = screen(b"a1 b22\r\nc333");
+        let search = Search::new(&screen, SearchQuery::regex(r"\d+").unwrap());
+        let matches: Vec<_> = search
+            .matches()
+            .iter()
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        assert_eq!(
+            matches,
+            [((0, 1), (0, 1)), ((0, 4), (0, 5)), ((1, 1), (1, 3))]
+        );
+    }
+}
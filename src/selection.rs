@@ -0,0 +1,300 @@
+//! Selection of text on a [`Screen`].
+//!
+//! A [`Selection`] is kept inside the [`PseudoTerminalState`] and is highlighted when the
+//! [`PseudoTerminal`] is rendered as a stateful widget.
+//!
+//! Positions are given as (line, column), like the matches of a [`Search`], where line `0` is
+//! the oldest row of the scrollback history and the rows of the screen follow after the last
+//! row of the history. A selection stays on the same text when the view is scrolled or when
+//! new output pushes the text into the history.
+//!
+//! [`PseudoTerminal`]: crate::widget::PseudoTerminal
+//! [`PseudoTerminalState`]: crate::widget::PseudoTerminalState
+//! [`Search`]: crate::search::Search
+
+use crate::{
+    state::{line_cell, line_wrapped},
+    widget::{Cell, Screen},
+};
+
+/// Characters that end a word when selecting words, besides whitespace.
+const WORD_SEPARATORS: &str = "()[]{}<>'\"`,;|│";
+
+/// The shape of a [`Selection`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectionMode {
+    /// Selects all cells between start and end in reading order,
+    /// like most terminals do by default.
+    #[default]
+    Linear,
+    /// Selects the rectangle spanned by start and end.
+    Block,
+}
+
+/// A selection of cells on a [`Screen`] and its scrollback history.
+///
+/// Both start and end are inclusive and given as (line, column).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Selection {
+    start: (usize, u16),
+    end: (usize, u16),
+    mode: SelectionMode,
+}
+
+impl Selection {
+    /// Creates a new selection of a single cell at `start`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::selection::{Selection, SelectionMode};
+    ///
+    /// let mut selection = Selection::new((0, 0), SelectionMode::Linear);
+    /// selection.extend((2, 5));
+    /// assert!(selection.contains(1, 42));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(start: (usize, u16), mode: SelectionMode) -> Self {
+        Self {
+            start,
+            end: start,
+            mode,
+        }
+    }
+
+    /// Creates a selection of the word around `position`.
+    ///
+    /// Returns a selection of the single cell at `position` if it is not part of a word.
+    #[must_use]
+    pub fn word<S: Screen>(screen: &S, position: (usize, u16)) -> Self {
+        let (line, col) = position;
+        let is_word = |col: u16| {
+            let cell = match line_cell(screen, line, col) {
+                // The column belongs to the wide character before it
+                Some(cell) if cell.is_wide_continuation() && col > 0 => {
+                    line_cell(screen, line, col - 1)
+                }
+                cell => cell,
+            };
+            cell.is_some_and(|cell| {
+                cell.symbol()
+                    .chars()
+                    .any(|c| !c.is_whitespace() && !WORD_SEPARATORS.contains(c))
+            })
+        };
+        if !is_word(col) {
+            return Self::new(position, SelectionMode::Linear);
+        }
+        let mut start = col;
+        while start > 0 && is_word(start - 1) {
+            start -= 1;
+        }
+        let mut end = col;
+        while end < u16::MAX && is_word(end + 1) {
+            end += 1;
+        }
+        Self {
+            start: (line, start),
+            end: (line, end),
+            mode: SelectionMode::Linear,
+        }
+    }
+
+    /// Creates a selection of the given line,
+    /// including the rows it is soft-wrapped across.
+    #[must_use]
+    pub fn line<S: Screen>(screen: &S, line: usize) -> Self {
+        let mut start = line;
+        while start > 0 && line_wrapped(screen, start - 1) {
+            start -= 1;
+        }
+        let mut end = line;
+        while line_wrapped(screen, end) && line_cell(screen, end + 1, 0).is_some() {
+            end += 1;
+        }
+        Self {
+            start: (start, 0),
+            end: (end, line_width(screen, end).saturating_sub(1)),
+            mode: SelectionMode::Linear,
+        }
+    }
+
+    /// Moves the end of the selection to `end`, keeping the start in place.
+    #[inline]
+    pub fn extend(&mut self, end: (usize, u16)) {
+        self.end = end;
+    }
+
+    /// Sets the [`SelectionMode`] of the selection.
+    #[inline]
+    pub fn set_mode(&mut self, mode: SelectionMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the position the selection was started at.
+    #[inline]
+    #[must_use]
+    pub const fn start(&self) -> (usize, u16) {
+        self.start
+    }
+
+    /// Returns the position the selection was extended to.
+    #[inline]
+    #[must_use]
+    pub const fn end(&self) -> (usize, u16) {
+        self.end
+    }
+
+    /// Returns the [`SelectionMode`] of the selection.
+    #[inline]
+    #[must_use]
+    pub const fn mode(&self) -> SelectionMode {
+        self.mode
+    }
+
+    /// Returns the start and end of the selection in reading order.
+    fn ordered(&self) -> ((usize, u16), (usize, u16)) {
+        if self.start <= self.end {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        }
+    }
+
+    /// Returns whether the cell at the given line and column is selected.
+    #[must_use]
+    pub fn contains(&self, line: usize, col: u16) -> bool {
+        match self.mode {
+            SelectionMode::Linear => {
+                let (start, end) = self.ordered();
+                start <= (line, col) && (line, col) <= end
+            }
+            SelectionMode::Block => {
+                let lines = self.start.0.min(self.end.0)..=self.start.0.max(self.end.0);
+                let cols = self.start.1.min(self.end.1)..=self.start.1.max(self.end.1);
+                lines.contains(&line) && cols.contains(&col)
+            }
+        }
+    }
+
+    /// Returns the selected text of `screen` and its scrollback history.
+    ///
+    /// Trailing whitespace is trimmed from every row.
+    /// In [`SelectionMode::Linear`] soft-wrapped rows are joined without a line break.
+    #[must_use]
+    pub fn text<S: Screen>(&self, screen: &S) -> String {
+        let (start, end) = self.ordered();
+        let (first_col, last_col) = match self.mode {
+            SelectionMode::Linear => (start.1, end.1),
+            SelectionMode::Block => (self.start.1.min(self.end.1), self.start.1.max(self.end.1)),
+        };
+        let mut text = String::new();
+        for line in start.0..=end.0 {
+            let (from, to) = match self.mode {
+                SelectionMode::Linear => (
+                    if line == start.0 { first_col } else { 0 },
+                    if line == end.0 { last_col } else { u16::MAX },
+                ),
+                SelectionMode::Block => (first_col, last_col),
+            };
+            let mut row = String::new();
+            let mut col = from;
+            while let Some(cell) = line_cell(screen, line, col) {
+                // The wide character before already covers this column
+                if !cell.is_wide_continuation() {
+                    row.push_str(&cell.symbol());
+                }
+                if col == to {
+                    break;
+                }
+                col += 1;
+            }
+            let wrapped =
+                self.mode == SelectionMode::Linear && line != end.0 && line_wrapped(screen, line);
+            if wrapped {
+                text.push_str(&row);
+            } else {
+                text.push_str(row.trim_end());
+                if line != end.0 {
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+}
+
+/// Returns the number of cells in the given line.
+fn line_width<S: Screen>(screen: &S, line: usize) -> u16 {
+    let mut width = 0;
+    while width < u16::MAX && line_cell(screen, line, width).is_some() {
+        width += 1;
+    }
+    width
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use super::*;
+
+    fn screen(stream: &[u8]) -> vt100::Screen {
+        let mut parser = vt100::Parser::new(4, 10, 0);
+        parser.process(stream);
+        parser.screen().clone()
+    }
+
+    #[test]
+    fn linear_text_joins_wrapped_rows() {
+        let screen = screen(b"hello wrapped world\r\nnext");
+        let mut selection = Selection::new((0, 6), SelectionMode::Linear);
+        selection.extend((2, 1));
+        assert_eq!(selection.text(&screen), "wrapped world\nne");
+    }
+
+    #[test]
+    fn reversed_linear_text() {
+        let screen = screen(b"first\r\nsecond");
+        let mut selection = Selection::new((1, 2), SelectionMode::Linear);
+        selection.extend((0, 3));
+        assert_eq!(selection.text(&screen), "st\nsec");
+    }
+
+    #[test]
+    fn block_text() {
+        let screen = screen(b"abcdef\r\nghijkl\r\nmnopqr");
+        let mut selection = Selection::new((2, 3), SelectionMode::Block);
+        selection.extend((0, 1));
+        assert!(selection.contains(1, 2));
+        assert!(!selection.contains(1, 4));
+        assert_eq!(selection.text(&screen), "bcd\nhij\nnop");
+    }
+
+    #[test]
+    fn word_selection() {
+        let screen = screen(b"cat (a.rs)");
+        let selection = Selection::word(&screen, (0, 6));
+        assert_eq!(selection.text(&screen), "a.rs");
+        let selection = Selection::word(&screen, (0, 3));
+        assert_eq!(selection.text(&screen), "");
+    }
+
+    #[test]
+    fn line_selection() {
+        let screen = screen(b"prompt\r\nhello wrapped world\r\nnext");
+        let selection = Selection::line(&screen, 2);
+        assert_eq!(selection.start(), (1, 0));
+        assert_eq!(selection.end(), (2, 9));
+        assert_eq!(selection.text(&screen), "hello wrapped world");
+    }
+
+    #[test]
+    fn wide_chars() {
+        let screen = screen("a中文b".as_bytes());
+        let mut selection = Selection::new((0, 0), SelectionMode::Linear);
+        selection.extend((0, 5));
+        assert_eq!(selection.text(&screen), "a中文b");
+        let selection = Selection::word(&screen, (0, 2));
+        assert_eq!(selection.text(&screen), "a中文b");
+    }
+}
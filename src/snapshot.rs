@@ -0,0 +1,306 @@
+//! An owned copy of a [`Screen`] that does not depend on the backend.
+//!
+//! A [`Snapshot`] copies the visible cells, the cursor, the title and the modes of any
+//! [`Screen`]. It implements [`Screen`] itself, so it can be rendered by the
+//! [`PseudoTerminal`], moved to another thread or compared with later snapshots.
+//! With the `serde` feature, snapshots can be serialized and deserialized.
+//!
+//! [`PseudoTerminal`]: crate::widget::PseudoTerminal
+
+use std::borrow::Cow;
+
+use ratatui::style::{Color, Modifier};
+
+use crate::{
+    state::screen_size,
+    widget::{Cell, CursorShape, MouseProtocolEncoding, MouseProtocolMode, Screen},
+};
+
+/// An owned copy of the visible contents and the state of a [`Screen`].
+///
+/// The scrollback history is not copied.
+///
+/// # Example
+///
+/// ```
+/// use tui_term::{snapshot::Snapshot, widget::PseudoTerminal};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// parser.process(b"hello");
+/// let snapshot = Snapshot::new(parser.screen());
+/// std::thread::spawn(move || {
+///     let pseudo_term = PseudoTerminal::new(&snapshot);
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    size: (u16, u16),
+    /// The cells in row-major order, `None` for cells that the screen does not have.
+    cells: Vec<Option<SnapshotCell>>,
+    wrapped: Vec<bool>,
+    cursor_position: (u16, u16),
+    hide_cursor: bool,
+    cursor_shape: Option<CursorShape>,
+    title: String,
+    icon_name: String,
+    alternate_screen: bool,
+    application_cursor: bool,
+    application_keypad: bool,
+    bracketed_paste: bool,
+    mouse_protocol_mode: MouseProtocolMode,
+    mouse_protocol_encoding: MouseProtocolEncoding,
+}
+
+impl Snapshot {
+    /// Copies the visible contents and the state of the screen.
+    #[must_use]
+    pub fn new<S: Screen>(screen: &S) -> Self {
+        let size = screen_size(screen);
+        let (rows, cols) = size;
+        let cells = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| screen.cell(row, col).map(SnapshotCell::new)))
+            .collect();
+        Self {
+            size,
+            cells,
+            wrapped: (0..rows).map(|row| screen.row_wrapped(row)).collect(),
+            cursor_position: screen.cursor_position(),
+            hide_cursor: screen.hide_cursor(),
+            cursor_shape: screen.cursor_shape(),
+            title: screen.title().into(),
+            icon_name: screen.icon_name().into(),
+            alternate_screen: screen.alternate_screen(),
+            application_cursor: screen.application_cursor(),
+            application_keypad: screen.application_keypad(),
+            bracketed_paste: screen.bracketed_paste(),
+            mouse_protocol_mode: screen.mouse_protocol_mode(),
+            mouse_protocol_encoding: screen.mouse_protocol_encoding(),
+        }
+    }
+}
+
+impl Screen for Snapshot {
+    type C = SnapshotCell;
+
+    #[inline]
+    fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+        let (rows, cols) = self.size;
+        if row >= rows || col >= cols {
+            return None;
+        }
+        // A deserialized snapshot may have fewer cells than its size
+        self.cells
+            .get(usize::from(row) * usize::from(cols) + usize::from(col))?
+            .as_ref()
+    }
+
+    #[inline]
+    fn hide_cursor(&self) -> bool {
+        self.hide_cursor
+    }
+
+    #[inline]
+    fn cursor_position(&self) -> (u16, u16) {
+        self.cursor_position
+    }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        self.wrapped.get(usize::from(row)).copied().unwrap_or(false)
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    #[inline]
+    fn icon_name(&self) -> &str {
+        &self.icon_name
+    }
+
+    #[inline]
+    fn alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
+    #[inline]
+    fn application_cursor(&self) -> bool {
+        self.application_cursor
+    }
+
+    #[inline]
+    fn application_keypad(&self) -> bool {
+        self.application_keypad
+    }
+
+    #[inline]
+    fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    #[inline]
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        self.mouse_protocol_mode
+    }
+
+    #[inline]
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        self.mouse_protocol_encoding
+    }
+
+    #[inline]
+    fn cursor_shape(&self) -> Option<CursorShape> {
+        self.cursor_shape
+    }
+}
+
+/// A cell of a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotCell {
+    symbol: String,
+    has_contents: bool,
+    fg: Color,
+    bg: Color,
+    modifiers: Modifier,
+    is_wide: bool,
+    is_wide_continuation: bool,
+}
+
+impl SnapshotCell {
+    /// Copies the contents and the style of the cell.
+    #[must_use]
+    pub fn new<C: Cell>(cell: &C) -> Self {
+        Self {
+            symbol: cell.symbol().into_owned(),
+            has_contents: cell.has_contents(),
+            fg: cell.fg(),
+            bg: cell.bg(),
+            modifiers: cell.modifiers(),
+            is_wide: cell.is_wide(),
+            is_wide_continuation: cell.is_wide_continuation(),
+        }
+    }
+}
+
+impl Cell for SnapshotCell {
+    #[inline]
+    fn has_contents(&self) -> bool {
+        self.has_contents
+    }
+
+    #[inline]
+    fn symbol(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.symbol)
+    }
+
+    #[inline]
+    fn fg(&self) -> Color {
+        self.fg
+    }
+
+    #[inline]
+    fn bg(&self) -> Color {
+        self.bg
+    }
+
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        self.modifiers
+    }
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.is_wide
+    }
+
+    #[inline]
+    fn is_wide_continuation(&self) -> bool {
+        self.is_wide_continuation
+    }
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::widget::PseudoTerminal;
+
+    fn parser() -> vt100::Parser {
+        let mut parser = vt100::Parser::new(4, 12, 0);
+        parser.process(b"\x1b]2;title\x07\x1b[?2004h\x1b[?1000h");
+        parser.process("\x1b[1;31mred\x1b[0m 中文 wrapped row\x1b[?25l".as_bytes());
+        parser
+    }
+
+    fn render<S: Screen>(screen: &S) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(12, 4)).unwrap();
+        terminal
+            .draw(|f| f.render_widget(PseudoTerminal::new(screen), f.area()))
+            .unwrap();
+        format!("{:?}", terminal.backend().buffer())
+    }
+
+    #[test]
+    fn copies_screen() {
+        let parser = parser();
+        let screen = parser.screen();
+        let snapshot = Snapshot::new(screen);
+        assert_eq!(Screen::size(&snapshot), (4, 12));
+        assert_eq!(Screen::title(&snapshot), "title");
+        assert!(Screen::bracketed_paste(&snapshot));
+        assert_eq!(
+            snapshot.mouse_protocol_mode(),
+            MouseProtocolMode::PressRelease
+        );
+        assert!(Screen::hide_cursor(&snapshot));
+        assert_eq!(
+            Screen::cursor_position(&snapshot),
+            Screen::cursor_position(screen)
+        );
+        assert!(Screen::row_wrapped(&snapshot, 0));
+        assert!(Screen::cell(&snapshot, 0, 5)
+            .unwrap()
+            .is_wide_continuation());
+        assert!(Screen::cell(&snapshot, 4, 0).is_none());
+        assert_eq!(render(&snapshot), render(screen));
+    }
+
+    #[test]
+    fn equality() {
+        let mut parser = parser();
+        let snapshot = Snapshot::new(parser.screen());
+        assert_eq!(snapshot, Snapshot::new(parser.screen()));
+        parser.process(b"!");
+        assert_ne!(snapshot, Snapshot::new(parser.screen()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let snapshot = Snapshot::new(parser().screen());
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<Snapshot>(&json).unwrap(), snapshot);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_missing_cells() {
+        let mut json = serde_json::to_value(Snapshot::new(parser().screen())).unwrap();
+        json["cells"].as_array_mut().unwrap().truncate(3);
+        json["wrapped"].as_array_mut().unwrap().clear();
+        let snapshot = serde_json::from_value::<Snapshot>(json).unwrap();
+        assert!(snapshot.cell(0, 2).is_some());
+        assert!(snapshot.cell(0, 3).is_none());
+        assert!(snapshot.cell(1, 0).is_none());
+        assert!(!snapshot.row_wrapped(0));
+    }
+}
@@ -1,10 +1,26 @@
-use ratatui::{buffer::Buffer, layout::Rect};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Position, Rect},
+    style::{Color, Modifier, Style},
+};
 
-use crate::widget::{Cell, PseudoTerminal, Screen};
+use crate::{
+    color::ColorDepth,
+    widget::{
+        Cell, CursorShape, PseudoTerminal, PseudoTerminalState, Screen, VerticalAlignment, Viewport,
+    },
+};
 
 /// Draw the [`Screen`] to the [`Buffer`],
 /// area is the designated area that the consumer provides
-pub fn handle<S: Screen>(term: &PseudoTerminal<S>, area: Rect, buf: &mut Buffer) {
+pub fn handle<S: Screen>(
+    term: &PseudoTerminal<S>,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &mut PseudoTerminalState,
+) {
+    let full_area = area;
+    let area = align(term, area, buf);
     let cols = area.width;
     let rows = area.height;
     let col_start = area.x;
@@ -12,6 +28,21 @@ pub fn handle<S: Screen>(term: &PseudoTerminal<S>, area: Rect, buf: &mut Buffer)
     let area_cols = area.width + area.x;
     let area_rows = area.height + area.y;
     let screen = term.screen();
+    let mut scrollback = state.scrollback.min(screen.scrollback_len());
+    let (mut row_offset, col_offset) = viewport_offset(term.viewport, screen, area);
+    if let Some(line) = state.reveal_line {
+        let max_row = screen_size(screen).0.saturating_sub(rows);
+        (scrollback, row_offset) = reveal_line(screen, line, scrollback, row_offset, rows);
+        row_offset = row_offset.min(max_row);
+    }
+    state.scrollback = scrollback;
+    state.area = area;
+    state.cursor_position = None;
+    state.viewport_offset = (row_offset, col_offset);
+    let base_style = term.style.unwrap_or_default();
+    // The line of the history that is shown in the first row
+    let top_line = screen.scrollback_len() - scrollback;
+    state.top_line = top_line;
 
     // The [`Screen`] is made out of rows of cells
     for row in 0..rows {
@@ -24,28 +55,293 @@ pub fn handle<S: Screen>(term: &PseudoTerminal<S>, area: Rect, buf: &mut Buffer)
                 continue;
             }
 
-            if let Some(screen_cell) = screen.cell(row, col) {
+            // The location of the cell in the view of the whole screen
+            let view_row = row + row_offset;
+            let view_col = col + col_offset;
+            if let Some(screen_cell) = view_cell(screen, scrollback, view_row, view_col) {
                 let cell = &mut buf[(buf_col, buf_row)];
                 screen_cell.apply(cell);
+                // The wide character to the left is drawn over a continuation cell.
+                // A wide character that is cut off by the edge of the area is not drawn,
+                // since it would spill over into the next cell.
+                if screen_cell.is_wide_continuation() || (screen_cell.is_wide() && col + 1 == cols)
+                {
+                    cell.set_symbol(" ");
+                }
+                // Colors set by the program win over the widget style,
+                // which in turn wins over the default colors of the palette
+                cell.fg = match (cell.fg, base_style.fg) {
+                    (Color::Reset, Some(fg)) => fg,
+                    (fg, _) => term
+                        .palette
+                        .as_ref()
+                        .map_or(fg, |palette| palette.foreground_color(fg)),
+                };
+                cell.bg = match (cell.bg, base_style.bg) {
+                    (Color::Reset, Some(bg)) => bg,
+                    (bg, _) => term
+                        .palette
+                        .as_ref()
+                        .map_or(bg, |palette| palette.background_color(bg)),
+                };
+                cell.modifier.insert(base_style.add_modifier);
+                let highlight = state.search.as_ref().and_then(|search| {
+                    search.highlight(top_line + usize::from(view_row), view_col)
+                });
+                if let Some(current) = highlight {
+                    let style = if current {
+                        term.current_search_style
+                    } else {
+                        term.search_style
+                    };
+                    set_overlay_style(cell, style, term.color_depth);
+                }
+                if state.selection.is_some_and(|selection| {
+                    selection.contains(top_line + usize::from(view_row), view_col)
+                }) {
+                    set_overlay_style(cell, term.selection_style, term.color_depth);
+                }
             }
         }
     }
 
     if !screen.hide_cursor() && term.cursor.show {
         let (c_row, c_col) = screen.cursor_position();
-        if (c_row + row_start) < area_rows && (c_col + col_start) < area_cols {
-            let c_cell = &mut buf[(c_col + col_start, c_row + row_start)];
-            if let Some(cell) = screen.cell(c_row, c_col) {
-                if cell.has_contents() {
-                    let style = term.cursor.overlay_style;
-                    c_cell.set_style(style);
-                } else {
-                    let symbol = &term.cursor.symbol;
-                    let style = term.cursor.style;
-                    c_cell.set_symbol(symbol);
-                    c_cell.set_style(style);
+        // The cursor moves down together with the screen when scrolled back
+        let c_row = u16::try_from(usize::from(c_row) + scrollback).ok();
+        if let Some(c_row) = c_row {
+            // The cursor is drawn on the wide character that covers its cell
+            let c_col = if c_col > 0
+                && view_cell(screen, scrollback, c_row, c_col)
+                    .is_some_and(Cell::is_wide_continuation)
+            {
+                c_col - 1
+            } else {
+                c_col
+            };
+            let row = c_row.checked_sub(row_offset).filter(|row| *row < rows);
+            let col = c_col.checked_sub(col_offset).filter(|col| *col < cols);
+            if let (Some(row), Some(col)) = (row, col) {
+                let position = Position::new(col + col_start, row + row_start);
+                state.cursor_position = Some(position);
+                let c_cell = &mut buf[position];
+                // The host terminal draws the hardware cursor itself
+                let cell =
+                    view_cell(screen, scrollback, c_row, c_col).filter(|_| !term.cursor.hardware);
+                if let Some(cell) = cell {
+                    let shape = term
+                        .cursor
+                        .shape
+                        .or_else(|| screen.cursor_shape())
+                        .unwrap_or_default();
+                    // An underline is drawn in the colors of the overlay, without reversing them
+                    let underline = term
+                        .cursor
+                        .overlay_style
+                        .remove_modifier(Modifier::REVERSED)
+                        .add_modifier(Modifier::UNDERLINED);
+                    if cell.has_contents() {
+                        match shape {
+                            CursorShape::Block => {
+                                set_overlay_style(
+                                    c_cell,
+                                    term.cursor.overlay_style,
+                                    term.color_depth,
+                                );
+                            }
+                            CursorShape::Underline => {
+                                c_cell.set_style(underline);
+                            }
+                            CursorShape::Bar => {
+                                // The bar is drawn at the right edge of an empty cell to the left,
+                                // which is the left edge of the cursor
+                                let left_empty = col > 0
+                                    && view_cell(screen, scrollback, c_row, c_col - 1).is_some_and(
+                                        |left| !left.is_wide_continuation() && left.symbol() == " ",
+                                    );
+                                if left_empty {
+                                    let left = &mut buf[(position.x - 1, position.y)];
+                                    left.set_symbol("\u{2595}"); // "▕"
+                                    left.set_style(term.cursor.style);
+                                } else {
+                                    c_cell.set_style(underline);
+                                }
+                            }
+                        }
+                    } else {
+                        let symbol = match shape {
+                            CursorShape::Block => &term.cursor.symbol,
+                            CursorShape::Underline => "\u{2581}", // "▁"
+                            CursorShape::Bar => "\u{258F}",       // "▏"
+                        };
+                        let style = term.cursor.style;
+                        c_cell.set_symbol(symbol);
+                        c_cell.set_style(style);
+                    }
                 }
             }
         }
     }
+
+    if term.color_depth != ColorDepth::TrueColor {
+        for position in full_area.positions() {
+            let cell = &mut buf[position];
+            cell.fg = term.color_depth.convert(cell.fg);
+            cell.bg = term.color_depth.convert(cell.bg);
+        }
+    }
+}
+
+/// Applies the style of an overlay, like the selection, to the cell.
+///
+/// Colors are not displayed in [`ColorDepth::Monochrome`], so the cell is reversed instead
+/// if the style relies on them.
+fn set_overlay_style(cell: &mut ratatui::buffer::Cell, style: Style, color_depth: ColorDepth) {
+    let reversed = cell.modifier.contains(Modifier::REVERSED);
+    cell.set_style(style);
+    let colored = [style.fg, style.bg]
+        .into_iter()
+        .any(|color| color.is_some_and(|color| color != Color::Reset));
+    if color_depth == ColorDepth::Monochrome && colored {
+        cell.modifier.set(Modifier::REVERSED, !reversed);
+    }
+}
+
+/// Returns the part of the area that the screen is drawn to, when the area is larger than
+/// the screen, and fills the rest of the area.
+fn align<S: Screen>(term: &PseudoTerminal<S>, area: Rect, buf: &mut Buffer) -> Rect {
+    let (rows, cols) = screen_size(term.screen());
+    if rows == 0 || cols == 0 {
+        // The screen has no cells
+        return area;
+    }
+    let width = cols.min(area.width);
+    let height = rows.min(area.height);
+    let x = match term.alignment {
+        Alignment::Left => area.x,
+        Alignment::Center => area.x + (area.width - width) / 2,
+        Alignment::Right => area.x + area.width - width,
+    };
+    let y = match term.vertical_alignment {
+        VerticalAlignment::Top => area.y,
+        VerticalAlignment::Center => area.y + (area.height - height) / 2,
+        VerticalAlignment::Bottom => area.y + area.height - height,
+    };
+    let screen_area = Rect::new(x, y, width, height);
+    for position in area.positions() {
+        if !screen_area.contains(position) {
+            let cell = &mut buf[position];
+            cell.set_symbol(&term.fill_symbol);
+            cell.set_style(term.fill_style);
+        }
+    }
+    screen_area
+}
+
+/// Returns the (row, column) of the view that is shown in the top left corner of the area.
+fn viewport_offset<S: Screen>(viewport: Viewport, screen: &S, area: Rect) -> (u16, u16) {
+    let (rows, cols) = screen_size(screen);
+    let max_row = rows.saturating_sub(area.height);
+    let max_col = cols.saturating_sub(area.width);
+    let (row, col) = match viewport {
+        Viewport::TopLeft => (0, 0),
+        Viewport::Bottom => (max_row, 0),
+        Viewport::FollowCursor => {
+            let (c_row, c_col) = screen.cursor_position();
+            (
+                c_row.saturating_sub(area.height.saturating_sub(1)),
+                c_col.saturating_sub(area.width.saturating_sub(1)),
+            )
+        }
+        Viewport::Offset { row, col } => (row, col),
+    };
+    (row.min(max_row), col.min(max_col))
+}
+
+/// Returns the scrollback and row offset that show the given line, counted from the top of the
+/// history, in an area of `rows` rows.
+///
+/// The view is scrolled to the line if it is out of the area,
+/// and the row offset is moved over the line if the history does not reach far enough.
+pub(crate) fn reveal_line<S: Screen>(
+    screen: &S,
+    line: usize,
+    scrollback: usize,
+    row_offset: u16,
+    rows: u16,
+) -> (usize, u16) {
+    let scrollback_len = screen.scrollback_len();
+    let top = scrollback_len - scrollback + usize::from(row_offset);
+    if (top..top + usize::from(rows)).contains(&line) {
+        return (scrollback, row_offset);
+    }
+    let scrollback = (scrollback_len + usize::from(row_offset))
+        .saturating_sub(line)
+        .min(scrollback_len);
+    let view_row =
+        u16::try_from(line - (scrollback_len - scrollback).min(line)).unwrap_or(u16::MAX);
+    let row_offset = if view_row < row_offset {
+        view_row
+    } else if view_row >= row_offset.saturating_add(rows) {
+        view_row.saturating_add(1).saturating_sub(rows)
+    } else {
+        row_offset
+    };
+    (scrollback, row_offset)
+}
+
+/// Returns the cell that is shown at the given location of the view,
+/// when the view is scrolled `scrollback` rows up into the history.
+pub(crate) fn view_cell<S: Screen>(
+    screen: &S,
+    scrollback: usize,
+    row: u16,
+    col: u16,
+) -> Option<&S::C> {
+    let row = usize::from(row);
+    if row < scrollback {
+        screen.scrollback_cell(scrollback - 1 - row, col)
+    } else {
+        screen.cell(u16::try_from(row - scrollback).ok()?, col)
+    }
+}
+
+/// Returns the (rows, columns) of the screen.
+///
+/// Backends that do not report their size are measured by their cells.
+pub(crate) fn screen_size<S: Screen>(screen: &S) -> (u16, u16) {
+    match screen.size() {
+        (0, _) | (_, 0) => {
+            let mut rows = 0;
+            while rows < u16::MAX && screen.cell(rows, 0).is_some() {
+                rows += 1;
+            }
+            let mut cols = 0;
+            while cols < u16::MAX && screen.cell(0, cols).is_some() {
+                cols += 1;
+            }
+            (rows, cols)
+        }
+        size => size,
+    }
+}
+
+/// Returns the cell at the given line, counted from the top of the history.
+pub(crate) fn line_cell<S: Screen>(screen: &S, line: usize, col: u16) -> Option<&S::C> {
+    let scrollback_len = screen.scrollback_len();
+    if line < scrollback_len {
+        screen.scrollback_cell(scrollback_len - 1 - line, col)
+    } else {
+        screen.cell(u16::try_from(line - scrollback_len).ok()?, col)
+    }
+}
+
+/// Returns whether the given line, counted from the top of the history, is soft-wrapped.
+pub(crate) fn line_wrapped<S: Screen>(screen: &S, line: usize) -> bool {
+    let scrollback_len = screen.scrollback_len();
+    if line < scrollback_len {
+        screen.scrollback_row_wrapped(scrollback_len - 1 - line)
+    } else {
+        u16::try_from(line - scrollback_len).is_ok_and(|row| screen.row_wrapped(row))
+    }
 }
@@ -0,0 +1,851 @@
+use std::{borrow::Cow, sync::Arc};
+
+use ratatui::style::{Color, Modifier};
+use termwiz::{
+    cell::{Blink, Cell as TermwizCell, CellAttributes, Intensity, Underline},
+    color::ColorAttribute,
+    escape::{
+        csi::{
+            Cursor as CsiCursor, CursorStyle, DecPrivateMode, DecPrivateModeCode, Edit,
+            EraseInDisplay, EraseInLine, Mode, Sgr,
+        },
+        parser::Parser,
+        Action, ControlCode, Esc, EscCode, OneBased, OperatingSystemCommand, CSI,
+    },
+    hyperlink::Hyperlink,
+    surface::{
+        line::CellRef, Change, CursorShape as TermwizCursorShape, CursorVisibility, Position,
+        Surface,
+    },
+};
+
+use crate::widget::{Cell, CursorShape, Screen};
+
+/// A [`termwiz::surface::Surface`] prepared for rendering.
+///
+/// A `Surface` only hands out all of its lines at once and leaves out the columns covered by
+/// wide characters. `SurfaceScreen` reads the lines once and fills those columns with
+/// continuation cells, so every column of the surface has a cell.
+///
+/// A surface is changed with [`termwiz::surface::Change`]s rather than escape sequences,
+/// so it is not a terminal emulator on its own. [`SurfaceParser`] applies the output of a
+/// pseudo-terminal to a surface.
+///
+/// The lines are read when the `SurfaceScreen` is created, so it has to be created again after
+/// changes were applied to the surface.
+///
+/// # Example
+///
+/// ```
+/// use termwiz::surface::Surface;
+/// use tui_term::{widget::PseudoTerminal, SurfaceScreen};
+///
+/// let mut surface = Surface::new(80, 24);
+/// surface.add_change("hello");
+/// let screen = SurfaceScreen::new(&surface);
+/// let pseudo_term = PseudoTerminal::new(&screen);
+/// ```
+pub struct SurfaceScreen<'a> {
+    surface: &'a Surface,
+    rows: Vec<Vec<SurfaceCell<'a>>>,
+    wrapped: Vec<bool>,
+}
+
+impl<'a> SurfaceScreen<'a> {
+    /// Reads the lines of the surface.
+    #[must_use]
+    pub fn new(surface: &'a Surface) -> Self {
+        let lines = surface.screen_lines();
+        let mut rows = Vec::with_capacity(lines.len());
+        let mut wrapped = Vec::with_capacity(lines.len());
+        for line in lines {
+            wrapped.push(line.last_cell_was_wrapped());
+            let mut row = Vec::with_capacity(line.len());
+            match line {
+                Cow::Borrowed(line) => {
+                    for cell in line.visible_cells() {
+                        let cell = match cell {
+                            CellRef::CellRef { cell, .. } => Cow::Borrowed(cell),
+                            CellRef::ClusterRef { .. } => Cow::Owned(cell.as_cell()),
+                        };
+                        push_cell(&mut row, cell);
+                    }
+                }
+                Cow::Owned(line) => {
+                    for cell in line.visible_cells() {
+                        push_cell(&mut row, Cow::Owned(cell.as_cell()));
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        Self {
+            surface,
+            rows,
+            wrapped,
+        }
+    }
+
+    /// Returns the surface.
+    #[inline]
+    #[must_use]
+    pub const fn surface(&self) -> &'a Surface {
+        self.surface
+    }
+}
+
+impl std::fmt::Debug for SurfaceScreen<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SurfaceScreen")
+            .field("rows", &self.rows)
+            .field("wrapped", &self.wrapped)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Adds the cell to the row, followed by a continuation cell
+/// for every further column it covers.
+fn push_cell<'a>(row: &mut Vec<SurfaceCell<'a>>, cell: Cow<'a, TermwizCell>) {
+    let width = cell.width();
+    row.push(SurfaceCell {
+        cell: cell.clone(),
+        continuation: false,
+    });
+    for _ in 1..width {
+        row.push(SurfaceCell {
+            cell: cell.clone(),
+            continuation: true,
+        });
+    }
+}
+
+/// A cell of a [`SurfaceScreen`].
+///
+/// The columns covered by a wide character are continuation cells
+/// that share the attributes of the wide character.
+#[derive(Debug, Clone)]
+pub struct SurfaceCell<'a> {
+    cell: Cow<'a, TermwizCell>,
+    continuation: bool,
+}
+
+impl SurfaceCell<'_> {
+    /// Returns the cell of the surface, which is the wide character for a continuation cell.
+    #[inline]
+    #[must_use]
+    pub fn cell(&self) -> &TermwizCell {
+        &self.cell
+    }
+
+    /// Returns the hyperlink of the cell, as set with an OSC 8 escape sequence.
+    #[inline]
+    #[must_use]
+    pub fn hyperlink(&self) -> Option<&Hyperlink> {
+        self.cell.attrs().hyperlink().map(Arc::as_ref)
+    }
+}
+
+impl<'a> Screen for SurfaceScreen<'a> {
+    type C = SurfaceCell<'a>;
+
+    #[inline]
+    fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+        self.rows.get(usize::from(row))?.get(usize::from(col))
+    }
+
+    #[inline]
+    fn hide_cursor(&self) -> bool {
+        self.surface.cursor_visibility() == CursorVisibility::Hidden
+    }
+
+    #[inline]
+    fn cursor_position(&self) -> (u16, u16) {
+        let (x, y) = self.surface.cursor_position();
+        (
+            u16::try_from(y).unwrap_or(u16::MAX),
+            u16::try_from(x).unwrap_or(u16::MAX),
+        )
+    }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        self.wrapped.get(usize::from(row)).copied().unwrap_or(false)
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        let (width, height) = self.surface.dimensions();
+        (
+            u16::try_from(height).unwrap_or(u16::MAX),
+            u16::try_from(width).unwrap_or(u16::MAX),
+        )
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        self.surface.title()
+    }
+
+    #[inline]
+    fn cursor_shape(&self) -> Option<CursorShape> {
+        match self.surface.cursor_shape()? {
+            TermwizCursorShape::Default => None,
+            TermwizCursorShape::BlinkingBlock | TermwizCursorShape::SteadyBlock => {
+                Some(CursorShape::Block)
+            }
+            TermwizCursorShape::BlinkingUnderline | TermwizCursorShape::SteadyUnderline => {
+                Some(CursorShape::Underline)
+            }
+            TermwizCursorShape::BlinkingBar | TermwizCursorShape::SteadyBar => {
+                Some(CursorShape::Bar)
+            }
+        }
+    }
+}
+
+impl Cell for SurfaceCell<'_> {
+    #[inline]
+    fn has_contents(&self) -> bool {
+        !self.continuation && Cell::has_contents(self.cell())
+    }
+
+    #[inline]
+    fn symbol(&self) -> Cow<'_, str> {
+        if self.continuation {
+            Cow::Borrowed(" ")
+        } else {
+            Cell::symbol(self.cell())
+        }
+    }
+
+    #[inline]
+    fn fg(&self) -> Color {
+        Cell::fg(self.cell())
+    }
+
+    #[inline]
+    fn bg(&self) -> Color {
+        Cell::bg(self.cell())
+    }
+
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        Cell::modifiers(self.cell())
+    }
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        !self.continuation && Cell::is_wide(self.cell())
+    }
+
+    #[inline]
+    fn is_wide_continuation(&self) -> bool {
+        self.continuation
+    }
+}
+
+impl Cell for TermwizCell {
+    #[inline]
+    fn has_contents(&self) -> bool {
+        // Blank cells are filled with a single space.
+        self.str() != " "
+    }
+
+    #[inline]
+    fn symbol(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.str())
+    }
+
+    #[inline]
+    fn fg(&self) -> Color {
+        convert_color(self.attrs().foreground())
+    }
+
+    #[inline]
+    fn bg(&self) -> Color {
+        convert_color(self.attrs().background())
+    }
+
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        let attrs = self.attrs();
+        let mut modifiers = Modifier::empty();
+        match attrs.intensity() {
+            Intensity::Normal => {}
+            Intensity::Bold => modifiers |= Modifier::BOLD,
+            Intensity::Half => modifiers |= Modifier::DIM,
+        }
+        if attrs.italic() {
+            modifiers |= Modifier::ITALIC;
+        }
+        if attrs.underline() != Underline::None {
+            modifiers |= Modifier::UNDERLINED;
+        }
+        match attrs.blink() {
+            Blink::None => {}
+            Blink::Slow => modifiers |= Modifier::SLOW_BLINK,
+            Blink::Rapid => modifiers |= Modifier::RAPID_BLINK,
+        }
+        if attrs.reverse() {
+            modifiers |= Modifier::REVERSED;
+        }
+        if attrs.invisible() {
+            modifiers |= Modifier::HIDDEN;
+        }
+        if attrs.strikethrough() {
+            modifiers |= Modifier::CROSSED_OUT;
+        }
+        modifiers
+    }
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.width() > 1
+    }
+}
+
+#[inline]
+fn convert_color(color: ColorAttribute) -> Color {
+    match color {
+        ColorAttribute::TrueColorWithPaletteFallback(rgba, _)
+        | ColorAttribute::TrueColorWithDefaultFallback(rgba) => {
+            let (r, g, b, _) = rgba.to_srgb_u8();
+            Color::Rgb(r, g, b)
+        }
+        ColorAttribute::PaletteIndex(i) => Color::Indexed(i),
+        ColorAttribute::Default => Color::Reset,
+    }
+}
+
+/// A terminal emulator that parses escape sequences into a [`termwiz::surface::Surface`].
+///
+/// The bytes are decoded by the [`termwiz::escape::parser::Parser`] and applied to the
+/// surface as [`Change`]s, so the output of a pseudo-terminal can be rendered with a
+/// [`SurfaceScreen`]. Hyperlinks are kept on the cells, see [`SurfaceCell::hyperlink`].
+///
+/// Printing, cursor movement, erasing, inserting and deleting lines, text attributes, the
+/// cursor shape and visibility, the title and hyperlinks are supported.
+/// A surface has neither a scrollback history nor scroll regions, so rows that scroll off the
+/// top of the screen are dropped and the whole screen is scrolled.
+///
+/// # Example
+///
+/// ```
+/// use tui_term::{
+///     widget::{PseudoTerminal, Screen},
+///     SurfaceParser,
+/// };
+///
+/// let mut parser = SurfaceParser::new(24, 80);
+/// parser.process(b"\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\");
+/// let screen = parser.screen();
+/// let link = screen.cell(0, 0).and_then(|cell| cell.hyperlink());
+/// assert_eq!(link.map(|link| link.uri()), Some("https://example.com"));
+/// let pseudo_term = PseudoTerminal::new(&screen);
+/// ```
+pub struct SurfaceParser {
+    parser: Parser,
+    surface: Surface,
+    attributes: CellAttributes,
+    saved_cursor: (usize, usize),
+}
+
+impl SurfaceParser {
+    /// Creates a parser with an empty surface of the given size.
+    #[must_use]
+    pub fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            parser: Parser::new(),
+            surface: Surface::new(usize::from(cols), usize::from(rows)),
+            attributes: CellAttributes::default(),
+            saved_cursor: (0, 0),
+        }
+    }
+
+    /// Parses the bytes and applies them to the surface.
+    pub fn process(&mut self, bytes: &[u8]) {
+        for action in self.parser.parse_as_vec(bytes) {
+            self.perform(action);
+        }
+        // The surface records every change to diff against, which is not needed here
+        self.surface
+            .flush_changes_older_than(self.surface.current_seqno());
+    }
+
+    /// Resizes the surface.
+    pub fn set_size(&mut self, rows: u16, cols: u16) {
+        self.surface.resize(usize::from(cols), usize::from(rows));
+    }
+
+    /// Returns the surface.
+    #[inline]
+    #[must_use]
+    pub const fn surface(&self) -> &Surface {
+        &self.surface
+    }
+
+    /// Returns the surface prepared for rendering.
+    #[inline]
+    #[must_use]
+    pub fn screen(&self) -> SurfaceScreen<'_> {
+        SurfaceScreen::new(&self.surface)
+    }
+
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::Print(c) => self.print(c.to_string()),
+            Action::PrintString(text) => self.print(text),
+            Action::Control(code) => self.control(code),
+            Action::CSI(CSI::Sgr(sgr)) => self.sgr(sgr),
+            Action::CSI(CSI::Cursor(cursor)) => self.cursor(cursor),
+            Action::CSI(CSI::Edit(edit)) => self.edit(edit),
+            Action::CSI(CSI::Mode(mode)) => self.mode(&mode),
+            Action::Esc(Esc::Code(code)) => self.esc(code),
+            Action::OperatingSystemCommand(osc) => self.osc(*osc),
+            _ => {}
+        }
+    }
+
+    fn print(&mut self, text: String) {
+        self.surface.add_change(Change::Text(text));
+    }
+
+    fn move_cursor(&mut self, x: Position, y: Position) {
+        self.surface.add_change(Change::CursorPosition { x, y });
+    }
+
+    /// Moves the cursor back to where it was, after a change that moved it.
+    fn restore_cursor(&mut self, (x, y): (usize, usize)) {
+        self.move_cursor(Position::Absolute(x), Position::Absolute(y));
+    }
+
+    fn apply_attributes(&mut self) {
+        let attributes = self.attributes.clone();
+        self.surface.add_change(Change::AllAttributes(attributes));
+    }
+
+    fn control(&mut self, code: ControlCode) {
+        match code {
+            ControlCode::LineFeed | ControlCode::VerticalTab | ControlCode::FormFeed => {
+                self.print("\n".to_owned());
+            }
+            ControlCode::CarriageReturn => {
+                self.move_cursor(Position::Absolute(0), Position::Relative(0));
+            }
+            ControlCode::Backspace => {
+                self.move_cursor(Position::Relative(-1), Position::Relative(0))
+            }
+            ControlCode::HorizontalTab => {
+                let (x, _) = self.surface.cursor_position();
+                self.move_cursor(Position::Absolute((x / 8 + 1) * 8), Position::Relative(0));
+            }
+            _ => {}
+        }
+    }
+
+    fn sgr(&mut self, sgr: Sgr) {
+        let attributes = &mut self.attributes;
+        match sgr {
+            Sgr::Reset => {
+                // A hyperlink is only closed by another OSC 8
+                let hyperlink = attributes.hyperlink().cloned();
+                *attributes = CellAttributes::default();
+                attributes.set_hyperlink(hyperlink)
+            }
+            Sgr::Intensity(intensity) => attributes.set_intensity(intensity),
+            Sgr::Underline(underline) => attributes.set_underline(underline),
+            Sgr::UnderlineColor(color) => attributes.set_underline_color(color),
+            Sgr::Blink(blink) => attributes.set_blink(blink),
+            Sgr::Italic(italic) => attributes.set_italic(italic),
+            Sgr::Inverse(reverse) => attributes.set_reverse(reverse),
+            Sgr::Invisible(invisible) => attributes.set_invisible(invisible),
+            Sgr::StrikeThrough(strikethrough) => attributes.set_strikethrough(strikethrough),
+            Sgr::Foreground(color) => attributes.set_foreground(color),
+            Sgr::Background(color) => attributes.set_background(color),
+            Sgr::Overline(overline) => attributes.set_overline(overline),
+            Sgr::VerticalAlign(align) => attributes.set_vertical_align(align),
+            Sgr::Font(_) => return,
+        };
+        self.apply_attributes();
+    }
+
+    fn cursor(&mut self, cursor: CsiCursor) {
+        let (x, y) = match cursor {
+            CsiCursor::Up(n) | CsiCursor::LinePositionBackward(n) => {
+                (Position::Relative(0), Position::Relative(-distance(n)))
+            }
+            CsiCursor::Down(n) | CsiCursor::LinePositionForward(n) => {
+                (Position::Relative(0), Position::Relative(distance(n)))
+            }
+            CsiCursor::Left(n) | CsiCursor::CharacterPositionBackward(n) => {
+                (Position::Relative(-distance(n)), Position::Relative(0))
+            }
+            CsiCursor::Right(n) | CsiCursor::CharacterPositionForward(n) => {
+                (Position::Relative(distance(n)), Position::Relative(0))
+            }
+            CsiCursor::NextLine(n) => (Position::Absolute(0), Position::Relative(distance(n))),
+            CsiCursor::PrecedingLine(n) => {
+                (Position::Absolute(0), Position::Relative(-distance(n)))
+            }
+            CsiCursor::Position { line, col }
+            | CsiCursor::CharacterAndLinePosition { line, col } => (absolute(col), absolute(line)),
+            CsiCursor::CharacterAbsolute(col) | CsiCursor::CharacterPositionAbsolute(col) => {
+                (absolute(col), Position::Relative(0))
+            }
+            CsiCursor::LinePositionAbsolute(line) => {
+                (Position::Relative(0), absolute(OneBased::new(line.max(1))))
+            }
+            CsiCursor::SaveCursor => {
+                self.saved_cursor = self.surface.cursor_position();
+                return;
+            }
+            CsiCursor::RestoreCursor => {
+                self.restore_cursor(self.saved_cursor);
+                return;
+            }
+            CsiCursor::CursorStyle(style) => {
+                let shape = match style {
+                    CursorStyle::Default => TermwizCursorShape::Default,
+                    CursorStyle::BlinkingBlock => TermwizCursorShape::BlinkingBlock,
+                    CursorStyle::SteadyBlock => TermwizCursorShape::SteadyBlock,
+                    CursorStyle::BlinkingUnderline => TermwizCursorShape::BlinkingUnderline,
+                    CursorStyle::SteadyUnderline => TermwizCursorShape::SteadyUnderline,
+                    CursorStyle::BlinkingBar => TermwizCursorShape::BlinkingBar,
+                    CursorStyle::SteadyBar => TermwizCursorShape::SteadyBar,
+                };
+                self.surface.add_change(Change::CursorShape(shape));
+                return;
+            }
+            _ => return,
+        };
+        self.move_cursor(x, y);
+    }
+
+    fn edit(&mut self, edit: Edit) {
+        let cursor @ (x, y) = self.surface.cursor_position();
+        let (width, height) = self.surface.dimensions();
+        let background = self.attributes.background();
+        match edit {
+            Edit::EraseInLine(EraseInLine::EraseToEndOfLine) => {
+                self.surface
+                    .add_change(Change::ClearToEndOfLine(background));
+            }
+            Edit::EraseInLine(EraseInLine::EraseToStartOfLine) => {
+                self.move_cursor(Position::Absolute(0), Position::Relative(0));
+                self.erase_cells(x + 1);
+                self.restore_cursor(cursor);
+            }
+            Edit::EraseInLine(EraseInLine::EraseLine) => {
+                self.move_cursor(Position::Absolute(0), Position::Relative(0));
+                self.surface
+                    .add_change(Change::ClearToEndOfLine(background));
+                self.restore_cursor(cursor);
+            }
+            Edit::EraseCharacter(n) => {
+                self.erase_cells(width.saturating_sub(x).min(count(n)));
+                self.restore_cursor(cursor);
+            }
+            Edit::EraseInDisplay(EraseInDisplay::EraseToEndOfDisplay) => {
+                self.surface
+                    .add_change(Change::ClearToEndOfScreen(background));
+            }
+            Edit::EraseInDisplay(EraseInDisplay::EraseToStartOfDisplay) => {
+                for row in 0..y {
+                    self.move_cursor(Position::Absolute(0), Position::Absolute(row));
+                    self.surface
+                        .add_change(Change::ClearToEndOfLine(background));
+                }
+                self.move_cursor(Position::Absolute(0), Position::Absolute(y));
+                self.erase_cells(x + 1);
+                self.restore_cursor(cursor);
+            }
+            Edit::EraseInDisplay(EraseInDisplay::EraseDisplay) => {
+                // Clearing the screen also resets the attributes and moves the cursor home
+                self.surface.add_change(Change::ClearScreen(background));
+                self.apply_attributes();
+                self.restore_cursor(cursor);
+            }
+            Edit::ScrollUp(n) => self.scroll_up(0, height, count(n)),
+            Edit::ScrollDown(n) => self.scroll_down(0, height, count(n)),
+            Edit::DeleteLine(n) => self.scroll_up(y, height - y, count(n)),
+            Edit::InsertLine(n) => self.scroll_down(y, height - y, count(n)),
+            _ => {}
+        }
+    }
+
+    /// Blanks the given number of cells from the cursor on, keeping the background color.
+    fn erase_cells(&mut self, cells: usize) {
+        let mut blank = CellAttributes::default();
+        blank.set_background(self.attributes.background());
+        self.surface.add_change(Change::AllAttributes(blank));
+        self.print(" ".repeat(cells));
+        self.apply_attributes();
+    }
+
+    fn scroll_up(&mut self, first_row: usize, region_size: usize, scroll_count: usize) {
+        let cursor = self.surface.cursor_position();
+        self.surface.add_change(Change::ScrollRegionUp {
+            first_row,
+            region_size,
+            scroll_count,
+        });
+        self.restore_cursor(cursor);
+    }
+
+    fn scroll_down(&mut self, first_row: usize, region_size: usize, scroll_count: usize) {
+        let cursor = self.surface.cursor_position();
+        self.surface.add_change(Change::ScrollRegionDown {
+            first_row,
+            region_size,
+            scroll_count,
+        });
+        self.restore_cursor(cursor);
+    }
+
+    fn mode(&mut self, mode: &Mode) {
+        let cursor = DecPrivateMode::Code(DecPrivateModeCode::ShowCursor);
+        let visibility = match mode {
+            Mode::SetDecPrivateMode(mode) if *mode == cursor => CursorVisibility::Visible,
+            Mode::ResetDecPrivateMode(mode) if *mode == cursor => CursorVisibility::Hidden,
+            _ => return,
+        };
+        self.surface
+            .add_change(Change::CursorVisibility(visibility));
+    }
+
+    fn esc(&mut self, code: EscCode) {
+        match code {
+            EscCode::Index => self.print("\n".to_owned()),
+            EscCode::NextLine => self.print("\r\n".to_owned()),
+            EscCode::ReverseIndex => {
+                let (_, height) = self.surface.dimensions();
+                if self.surface.cursor_position().1 == 0 {
+                    self.scroll_down(0, height, 1);
+                } else {
+                    self.move_cursor(Position::Relative(0), Position::Relative(-1));
+                }
+            }
+            EscCode::DecSaveCursorPosition => self.saved_cursor = self.surface.cursor_position(),
+            EscCode::DecRestoreCursorPosition => self.restore_cursor(self.saved_cursor),
+            EscCode::FullReset => {
+                let (width, height) = self.surface.dimensions();
+                self.surface = Surface::new(width, height);
+                self.attributes = CellAttributes::default();
+                self.saved_cursor = (0, 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn osc(&mut self, osc: OperatingSystemCommand) {
+        match osc {
+            OperatingSystemCommand::SetIconNameAndWindowTitle(title)
+            | OperatingSystemCommand::SetWindowTitle(title) => {
+                self.surface.add_change(Change::Title(title));
+            }
+            OperatingSystemCommand::SetHyperlink(hyperlink) => {
+                self.attributes.set_hyperlink(hyperlink.map(Arc::new));
+                self.apply_attributes();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Debug for SurfaceParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SurfaceParser")
+            .field("attributes", &self.attributes)
+            .field("saved_cursor", &self.saved_cursor)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the distance of a relative cursor movement.
+fn distance(n: u32) -> isize {
+    isize::try_from(n).unwrap_or(isize::MAX)
+}
+
+/// Returns the number of cells or rows of an escape sequence parameter.
+fn count(n: u32) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+/// Returns the absolute position of a one-based escape sequence parameter.
+fn absolute(n: OneBased) -> Position {
+    Position::Absolute(count(n.as_zero_based()))
+}
+
+#[cfg(test)]
+mod tests {
+    use termwiz::{
+        cell::AttributeChange,
+        color::{AnsiColor, RgbColor},
+        surface::{Change, Position},
+    };
+
+    use super::*;
+    use crate::{
+        search::{Search, SearchQuery},
+        selection::{Selection, SelectionMode},
+        testing::snapshot_screen,
+    };
+
+    #[test]
+    fn cells() {
+        let mut surface = Surface::new(10, 3);
+        surface.add_change("a😀b");
+        let screen = SurfaceScreen::new(&surface);
+        assert_eq!(screen.cell(0, 0).unwrap().symbol(), "a");
+        let wide = screen.cell(0, 1).unwrap();
+        assert_eq!(wide.symbol(), "😀");
+        assert!(wide.is_wide());
+        let continuation = screen.cell(0, 2).unwrap();
+        assert_eq!(continuation.symbol(), " ");
+        assert!(!continuation.has_contents());
+        assert!(continuation.is_wide_continuation());
+        assert_eq!(screen.cell(0, 3).unwrap().symbol(), "b");
+        assert!(!screen.cell(0, 4).unwrap().has_contents());
+        assert!(screen.cell(3, 0).is_none());
+        assert!(screen.cell(0, 10).is_none());
+    }
+
+    #[test]
+    fn wide_chars() {
+        let mut surface = Surface::new(10, 3);
+        surface.add_change("a中文b");
+        let screen = SurfaceScreen::new(&surface);
+        let mut selection = Selection::new((0, 0), SelectionMode::Linear);
+        selection.extend((0, 5));
+        assert_eq!(selection.text(&screen), "a中文b");
+        let search = Search::new(&screen, SearchQuery::literal("文b"));
+        let found = search.matches()[0];
+        assert_eq!((found.start(), found.end()), ((0, 3), (0, 5)));
+    }
+
+    #[test]
+    fn cursor() {
+        let mut surface = Surface::new(10, 3);
+        surface.add_change(Change::CursorPosition {
+            x: Position::Absolute(4),
+            y: Position::Absolute(2),
+        });
+        assert_eq!(SurfaceScreen::new(&surface).cursor_position(), (2, 4));
+        assert!(!SurfaceScreen::new(&surface).hide_cursor());
+        surface.add_change(Change::CursorVisibility(CursorVisibility::Hidden));
+        assert!(SurfaceScreen::new(&surface).hide_cursor());
+        surface.add_change(Change::CursorShape(TermwizCursorShape::SteadyUnderline));
+        assert_eq!(
+            SurfaceScreen::new(&surface).cursor_shape(),
+            Some(CursorShape::Underline)
+        );
+    }
+
+    #[test]
+    fn size_and_title() {
+        let mut surface = Surface::new(10, 3);
+        surface.add_change(Change::Title("title".into()));
+        let screen = SurfaceScreen::new(&surface);
+        assert_eq!(screen.size(), (3, 10));
+        assert_eq!(screen.title(), "title");
+    }
+
+    #[test]
+    fn attributes() {
+        let mut surface = Surface::new(80, 24);
+        surface.add_changes(vec![
+            Change::Attribute(AttributeChange::Intensity(Intensity::Bold)),
+            Change::Attribute(AttributeChange::Foreground(AnsiColor::Red.into())),
+            Change::Text("bold red".into()),
+            Change::AllAttributes(Default::default()),
+            Change::Text(" ".into()),
+            Change::Attribute(AttributeChange::Intensity(Intensity::Half)),
+            Change::Attribute(AttributeChange::StrikeThrough(true)),
+            Change::Text("dim crossed out".into()),
+            Change::AllAttributes(Default::default()),
+            Change::Text(" ".into()),
+            Change::Attribute(AttributeChange::Italic(true)),
+            Change::Attribute(AttributeChange::Underline(Underline::Curly)),
+            Change::Attribute(AttributeChange::Background(
+                ColorAttribute::TrueColorWithDefaultFallback(
+                    RgbColor::new_8bpc(0x12, 0x34, 0x56).into(),
+                ),
+            )),
+            Change::Text("italic underlined".into()),
+            Change::AllAttributes(Default::default()),
+            Change::Text(" ".into()),
+            Change::Attribute(AttributeChange::Reverse(true)),
+            Change::Text("reversed".into()),
+            Change::AllAttributes(Default::default()),
+            Change::Text(" plain".into()),
+        ]);
+        let view = snapshot_screen(&SurfaceScreen::new(&surface));
+        insta::assert_snapshot!(view);
+    }
+
+    #[test]
+    fn parser_hyperlinks() {
+        let mut parser = SurfaceParser::new(3, 20);
+        parser.process(b"\x1b]8;;https://example.com\x1b\\\x1b[1mli\x1b[0mnk");
+        parser.process(b"\x1b]8;;\x1b\\ plain");
+        let screen = parser.screen();
+        let uri = |col| {
+            let cell = screen.cell(0, col).unwrap();
+            cell.hyperlink().map(|link| link.uri().to_owned())
+        };
+        assert_eq!(uri(0).as_deref(), Some("https://example.com"));
+        // The link is kept when the attributes are reset
+        assert_eq!(uri(3).as_deref(), Some("https://example.com"));
+        assert_eq!(uri(5), None);
+        assert_eq!(screen.cell(0, 1).unwrap().modifiers(), Modifier::BOLD);
+        assert_eq!(screen.cell(0, 2).unwrap().modifiers(), Modifier::empty());
+    }
+
+    #[test]
+    fn parser_edits() {
+        let mut parser = SurfaceParser::new(4, 10);
+        parser.process(b"\x1b]2;title\x07first\r\nsecond\r\nthird\r\nfourth");
+        // Erase the start of the last line and delete the second line
+        parser.process(b"\x1b[3D\x1b[1K\x1b[2;1H\x1b[M");
+        // Insert a line above the first one and write into it
+        parser.process(b"\x1b[H\x1b[L\x1b[31mnew\x1b[?25l\x1b[4 q");
+        let screen = parser.screen();
+        assert_eq!(screen.title(), "title");
+        assert_eq!(
+            parser.surface().screen_chars_to_string(),
+            "new       \nfirst     \nthird     \n    th    \n"
+        );
+        assert_eq!(screen.cell(0, 0).unwrap().fg(), Color::Indexed(1));
+        assert_eq!(screen.cursor_position(), (0, 3));
+        assert!(screen.hide_cursor());
+        assert_eq!(screen.cursor_shape(), Some(CursorShape::Underline));
+    }
+
+    /// Renders the typescript with both backends, which need to agree unless the typescript
+    /// uses scroll regions, or characters that termwiz considers to be of another width.
+    #[cfg(feature = "vt100")]
+    #[test]
+    fn parser_matches_vt100() {
+        let streams: [&[u8]; 9] = [
+            include_bytes!("../test/typescript/vttest_02_01.typescript"),
+            include_bytes!("../test/typescript/vttest_02_02.typescript"),
+            include_bytes!("../test/typescript/vttest_02_03.typescript"),
+            include_bytes!("../test/typescript/vttest_02_05.typescript"),
+            include_bytes!("../test/typescript/vttest_02_06.typescript"),
+            include_bytes!("../test/typescript/vttest_02_08.typescript"),
+            include_bytes!("../test/typescript/vttest_02_10.typescript"),
+            include_bytes!("../test/typescript/vttest_02_12.typescript"),
+            include_bytes!("../test/typescript/vttest_02_14.typescript"),
+        ];
+        for stream in streams {
+            let mut vt100 = vt100::Parser::new(24, 80, 0);
+            vt100.process(stream);
+            let mut parser = SurfaceParser::new(24, 80);
+            parser.process(stream);
+            assert_eq!(
+                snapshot_screen(&parser.screen()),
+                snapshot_screen(vt100.screen())
+            );
+        }
+    }
+}
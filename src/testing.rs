@@ -0,0 +1,38 @@
+//! Helpers shared by the tests of the widgets and the backends.
+
+use ratatui::{backend::TestBackend, widgets::Widget, Frame, Terminal};
+
+use crate::widget::{PseudoTerminal, Screen};
+
+/// Draws a frame of the given size with `render` and formats the buffer for snapshot tests.
+pub(crate) fn snapshot_frame(width: u16, height: u16, render: impl FnOnce(&mut Frame)) -> String {
+    let backend = TestBackend::new(width, height);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(render).unwrap();
+    format!("{:?}", terminal.backend().buffer())
+}
+
+/// Renders the widget over a buffer of the given size and formats the buffer for snapshot tests.
+pub(crate) fn snapshot_widget<W: Widget>(widget: W, width: u16, height: u16) -> String {
+    snapshot_frame(width, height, |f| f.render_widget(widget, f.area()))
+}
+
+/// Renders the widget with the state over a buffer of the given size and formats the buffer
+/// for snapshot tests.
+#[cfg(feature = "vt100")]
+pub(crate) fn snapshot_stateful<W: ratatui::widgets::StatefulWidget>(
+    widget: W,
+    state: &mut W::State,
+    width: u16,
+    height: u16,
+) -> String {
+    snapshot_frame(width, height, |f| {
+        f.render_stateful_widget(widget, f.area(), state);
+    })
+}
+
+/// Renders the screen with a [`PseudoTerminal`] into an 80x24 buffer and formats the buffer
+/// for snapshot tests.
+pub(crate) fn snapshot_screen<S: Screen>(screen: &S) -> String {
+    snapshot_widget(PseudoTerminal::new(screen), 80, 24)
+}
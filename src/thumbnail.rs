@@ -0,0 +1,437 @@
+//! A downscaled rendering of a [`Screen`], to show many terminals at a glance.
+//!
+//! The [`PseudoTerminalThumbnail`] splits its area into pixels, either two per cell with
+//! half blocks or eight per cell with braille dots, and maps each pixel to a region of the
+//! screen. A pixel is drawn in the averaged foreground color of the region when at least
+//! half of its cells show a symbol, otherwise in the averaged background color.
+//!
+//! [`Screen`]: crate::widget::Screen
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    widgets::{Block, Clear, Widget},
+};
+
+use crate::{
+    color::Palette,
+    state::screen_size,
+    widget::{Cell, Screen},
+};
+
+/// The characters that a [`PseudoTerminalThumbnail`] is drawn with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailMode {
+    /// Two pixels per cell, drawn with the upper and lower half block characters.
+    ///
+    /// Each pixel has its own color.
+    #[default]
+    HalfBlock,
+    /// Eight pixels per cell, drawn with braille dots.
+    ///
+    /// All dots of a cell share the same color, but the shapes are more detailed.
+    Braille,
+}
+
+/// A widget that renders a downscaled [`Screen`].
+///
+/// # Example
+///
+/// ```rust
+/// use ratatui::widgets::{Block, Borders};
+/// use tui_term::thumbnail::{PseudoTerminalThumbnail, ThumbnailMode};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 0);
+/// let thumbnail = PseudoTerminalThumbnail::new(parser.screen())
+///     .block(Block::default().title("htop").borders(Borders::ALL))
+///     .mode(ThumbnailMode::Braille);
+/// ```
+#[non_exhaustive]
+pub struct PseudoTerminalThumbnail<'a, S> {
+    screen: &'a S,
+    pub(crate) block: Option<Block<'a>>,
+    pub(crate) mode: ThumbnailMode,
+    pub(crate) palette: Palette,
+}
+
+impl<'a, S: Screen> PseudoTerminalThumbnail<'a, S> {
+    /// Creates a new instance of `PseudoTerminalThumbnail`.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen`: The reference to the `Screen`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::thumbnail::PseudoTerminalThumbnail;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let thumbnail = PseudoTerminalThumbnail::new(parser.screen());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(screen: &'a S) -> Self {
+        PseudoTerminalThumbnail {
+            screen,
+            block: None,
+            mode: ThumbnailMode::HalfBlock,
+            palette: Palette::xterm(),
+        }
+    }
+
+    /// Sets the block for the `PseudoTerminalThumbnail`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block`: The `Block` to set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::widgets::Block;
+    /// use tui_term::thumbnail::PseudoTerminalThumbnail;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let thumbnail = PseudoTerminalThumbnail::new(parser.screen()).block(Block::default());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the characters that the thumbnail is drawn with.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`: The `ThumbnailMode` to draw with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::thumbnail::{PseudoTerminalThumbnail, ThumbnailMode};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let thumbnail = PseudoTerminalThumbnail::new(parser.screen()).mode(ThumbnailMode::Braille);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn mode(mut self, mode: ThumbnailMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the palette that the colors of the cells are resolved with before they are
+    /// averaged.
+    ///
+    /// Defaults to [`Palette::xterm`]. Default colors that the palette leaves to the host
+    /// terminal cannot be averaged, so a pixel keeps the default color when most of its
+    /// cells use it.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The `Palette` to resolve colors with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::{color::Palette, thumbnail::PseudoTerminalThumbnail};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let thumbnail =
+    ///     PseudoTerminalThumbnail::new(parser.screen()).palette(Palette::solarized_dark());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Returns the pixel of the screen region that spans the given rows and columns.
+    fn pixel(&self, rows: (u16, u16), cols: (u16, u16)) -> Pixel {
+        let mut cells = 0;
+        let mut ink = Average::default();
+        let mut paper = Average::default();
+        for row in rows.0..rows.1 {
+            for col in cols.0..cols.1 {
+                let Some(cell) = self.screen.cell(row, col) else {
+                    continue;
+                };
+                let modifiers = cell.modifiers();
+                let (fg, bg) = if modifiers.contains(Modifier::REVERSED) {
+                    (
+                        self.palette.background_color(cell.bg()),
+                        self.palette.foreground_color(cell.fg()),
+                    )
+                } else {
+                    (
+                        self.palette.foreground_color(cell.fg()),
+                        self.palette.background_color(cell.bg()),
+                    )
+                };
+                cells += 1;
+                // Written spaces are contents, but are not visible
+                let visible = cell.has_contents() && !cell.symbol().trim().is_empty();
+                if visible && !modifiers.contains(Modifier::HIDDEN) {
+                    ink.add(fg);
+                }
+                paper.add(bg);
+            }
+        }
+        if ink.count > 0 && ink.count * 2 >= cells {
+            Pixel {
+                color: ink.color(),
+                ink: true,
+            }
+        } else {
+            Pixel {
+                color: paper.color(),
+                ink: false,
+            }
+        }
+    }
+
+    fn render_half_blocks(&self, area: Rect, buf: &mut Buffer, size: (u16, u16)) {
+        let rows = Scale::new(size.0, u32::from(area.height) * 2);
+        let cols = Scale::new(size.1, u32::from(area.width));
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let (y2, x1) = (u32::from(y) * 2, u32::from(x));
+                let top = self.pixel(rows.range(y2), cols.range(x1));
+                let bottom = self.pixel(rows.range(y2 + 1), cols.range(x1));
+                let (symbol, fg, bg) = half_block(top, bottom);
+                buf[(area.x + x, area.y + y)]
+                    .set_symbol(symbol)
+                    .set_fg(fg)
+                    .set_bg(bg);
+            }
+        }
+    }
+
+    fn render_braille(&self, area: Rect, buf: &mut Buffer, size: (u16, u16)) {
+        // The bits of the braille dots, by row and column within a cell
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        let rows = Scale::new(size.0, u32::from(area.height) * 4);
+        let cols = Scale::new(size.1, u32::from(area.width) * 2);
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let mut dots = 0;
+                let mut ink = Average::default();
+                let mut paper = Average::default();
+                for (dy, bits) in (0..).zip(DOTS) {
+                    for (dx, bit) in (0..).zip(bits) {
+                        let row = rows.range(u32::from(y) * 4 + dy);
+                        let pixel = self.pixel(row, cols.range(u32::from(x) * 2 + dx));
+                        if pixel.ink {
+                            dots |= bit;
+                            ink.add(pixel.color);
+                        } else {
+                            paper.add(pixel.color);
+                        }
+                    }
+                }
+                let symbol = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                buf[(area.x + x, area.y + y)]
+                    .set_char(symbol)
+                    .set_fg(ink.color())
+                    .set_bg(paper.color());
+            }
+        }
+    }
+}
+
+impl<S: Screen> Widget for PseudoTerminalThumbnail<'_, S> {
+    #[inline]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let area = self.block.as_ref().map_or(area, |b| {
+            let inner_area = b.inner(area);
+            b.clone().render(area, buf);
+            inner_area
+        });
+        let size = screen_size(self.screen);
+        if area.is_empty() || size.0 == 0 || size.1 == 0 {
+            return;
+        }
+        match self.mode {
+            ThumbnailMode::HalfBlock => self.render_half_blocks(area, buf, size),
+            ThumbnailMode::Braille => self.render_braille(area, buf, size),
+        }
+    }
+}
+
+/// The averaged color of a region of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pixel {
+    color: Color,
+    /// Whether the pixel shows the contents of the cells rather than their background.
+    ink: bool,
+}
+
+impl Pixel {
+    /// Whether the pixel can be drawn with the foreground color of a cell.
+    ///
+    /// [`Color::Reset`] means the default background color for pixels that are not inked,
+    /// which only the background color of a cell can show, and vice versa.
+    fn fits_fg(self) -> bool {
+        self.ink || self.color != Color::Reset
+    }
+
+    fn fits_bg(self) -> bool {
+        !self.ink || self.color != Color::Reset
+    }
+}
+
+/// Returns the symbol, foreground and background color of a cell with two pixels.
+fn half_block(top: Pixel, bottom: Pixel) -> (&'static str, Color, Color) {
+    if top.color == bottom.color && (top.ink == bottom.ink || top.color != Color::Reset) {
+        if top.fits_bg() {
+            (" ", Color::Reset, top.color)
+        } else {
+            ("█", top.color, Color::Reset)
+        }
+    } else if top.fits_fg() && bottom.fits_bg() {
+        ("▀", top.color, bottom.color)
+    } else {
+        // Both pixels are the other way around, so the lower half block fits
+        ("▄", bottom.color, top.color)
+    }
+}
+
+/// An accumulator for the average of colors.
+///
+/// RGB colors are averaged, the other colors cannot be mixed and win when they are the
+/// majority.
+#[derive(Debug, Default)]
+struct Average {
+    count: u32,
+    rgb: [u32; 3],
+    rgb_count: u32,
+    other: Option<Color>,
+}
+
+impl Average {
+    fn add(&mut self, color: Color) {
+        self.count += 1;
+        if let Color::Rgb(r, g, b) = color {
+            self.rgb[0] += u32::from(r);
+            self.rgb[1] += u32::from(g);
+            self.rgb[2] += u32::from(b);
+            self.rgb_count += 1;
+        } else {
+            self.other.get_or_insert(color);
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.other {
+            Some(color) if self.rgb_count * 2 <= self.count => color,
+            _ if self.rgb_count == 0 => Color::Reset,
+            _ => {
+                let channel = |sum: u32| u8::try_from(sum / self.rgb_count).unwrap_or(u8::MAX);
+                Color::Rgb(
+                    channel(self.rgb[0]),
+                    channel(self.rgb[1]),
+                    channel(self.rgb[2]),
+                )
+            }
+        }
+    }
+}
+
+/// Maps the pixels of the thumbnail to the rows or columns of the screen.
+#[derive(Debug, Clone, Copy)]
+struct Scale {
+    screen: u16,
+    pixels: u32,
+}
+
+impl Scale {
+    const fn new(screen: u16, pixels: u32) -> Self {
+        Self { screen, pixels }
+    }
+
+    /// Returns the range of rows or columns of the screen that the pixel covers.
+    ///
+    /// Every pixel covers at least one row or column, so screens that are smaller than the
+    /// thumbnail are scaled up.
+    fn range(self, pixel: u32) -> (u16, u16) {
+        let scale = |pixel: u32| {
+            let position = u64::from(pixel) * u64::from(self.screen) / u64::from(self.pixels);
+            u16::try_from(position).unwrap_or(u16::MAX)
+        };
+        let start = scale(pixel).min(self.screen - 1);
+        (start, scale(pixel + 1).max(start + 1))
+    }
+}
+
+#[cfg(all(test, feature = "vt100"))]
+mod tests {
+    use ratatui::{backend::TestBackend, widgets::Borders, Terminal};
+
+    use super::*;
+    use crate::testing::snapshot_widget;
+
+    fn snapshot_typescript(stream: &[u8], mode: ThumbnailMode) -> String {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(stream);
+        let thumbnail = PseudoTerminalThumbnail::new(parser.screen())
+            .block(Block::default().borders(Borders::ALL))
+            .mode(mode);
+        snapshot_widget(thumbnail, 22, 8)
+    }
+
+    #[test]
+    fn half_blocks() {
+        let stream = include_bytes!("../test/typescript/simple_ls.typescript");
+        let view = snapshot_typescript(stream, ThumbnailMode::HalfBlock);
+        insta::assert_snapshot!(view);
+    }
+
+    #[test]
+    fn braille() {
+        let stream = include_bytes!("../test/typescript/simple_ls.typescript");
+        let view = snapshot_typescript(stream, ThumbnailMode::Braille);
+        insta::assert_snapshot!(view);
+    }
+
+    #[test]
+    fn averaged_colors() {
+        let backend = TestBackend::new(2, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut parser = vt100::Parser::new(2, 4, 0);
+        parser.process(b"\x1b[48;2;200;0;0m  \x1b[48;2;0;0;100m  \r\n");
+        parser.process(b"\x1b[38;2;0;200;0mab\x1b[0m\x1b[48;2;0;0;200m  \x1b[H");
+        terminal
+            .draw(|f| {
+                let thumbnail = PseudoTerminalThumbnail::new(parser.screen());
+                f.render_widget(thumbnail, f.area());
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 0)].symbol(), "▀");
+        assert_eq!(buffer[(0, 0)].fg, Color::Rgb(200, 0, 0));
+        assert_eq!(buffer[(0, 0)].bg, Color::Rgb(0, 200, 0));
+        assert_eq!(buffer[(1, 0)].symbol(), "▀");
+        assert_eq!(buffer[(1, 0)].fg, Color::Rgb(0, 0, 100));
+        assert_eq!(buffer[(1, 0)].bg, Color::Rgb(0, 0, 200));
+    }
+
+    #[test]
+    fn scale() {
+        let scale = Scale::new(80, 20);
+        assert_eq!(scale.range(0), (0, 4));
+        assert_eq!(scale.range(19), (76, 80));
+        let scale = Scale::new(3, 8);
+        assert_eq!(scale.range(0), (0, 1));
+        assert_eq!(scale.range(7), (2, 3));
+        // Braille pixels of the tallest area do not fit into a u16
+        let pixels = u32::from(u16::MAX) * 4;
+        let scale = Scale::new(u16::MAX, pixels);
+        assert_eq!(scale.range(pixels - 1), (u16::MAX - 1, u16::MAX));
+    }
+}
@@ -1,7 +1,11 @@
-use ratatui::style::{Modifier, Style};
+use std::borrow::Cow;
 
-use crate::widget::{Cell, Screen};
+use ratatui::style::Modifier;
 
+use crate::widget::{Cell, MouseProtocolEncoding, MouseProtocolMode, Screen};
+
+// `vt100::Screen` does not hand out its scrollback history, so the scrollback
+// accessors keep their defaults. `ScrollbackScreen` reads the history from the parser.
 impl Screen for vt100::Screen {
     type C = vt100::Cell;
 
@@ -19,6 +23,270 @@ impl Screen for vt100::Screen {
     fn cursor_position(&self) -> (u16, u16) {
         self.cursor_position()
     }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        self.row_wrapped(row)
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        self.size()
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        self.title()
+    }
+
+    #[inline]
+    fn icon_name(&self) -> &str {
+        self.icon_name()
+    }
+
+    #[inline]
+    fn alternate_screen(&self) -> bool {
+        self.alternate_screen()
+    }
+
+    #[inline]
+    fn application_cursor(&self) -> bool {
+        self.application_cursor()
+    }
+
+    #[inline]
+    fn application_keypad(&self) -> bool {
+        self.application_keypad()
+    }
+
+    #[inline]
+    fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste()
+    }
+
+    #[inline]
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        self.mouse_protocol_mode().into()
+    }
+
+    #[inline]
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        self.mouse_protocol_encoding().into()
+    }
+}
+
+/// A [`vt100::Screen`] together with rows of the scrollback history of its [`vt100::Parser`].
+///
+/// `vt100` only shows its history by shifting the rows of the screen with
+/// [`vt100::Parser::set_scrollback`], so a plain [`vt100::Screen`] has no history to scroll
+/// through. `ScrollbackScreen` copies the requested rows of the history out of the parser and
+/// hands them out through [`Screen::scrollback_cell`], which lets
+/// [`PseudoTerminal::scrollback`] and [`PseudoTerminalState`] scroll up into it.
+///
+/// Only the rows of the history that a view scrolled up `scrollback` rows and `rows` rows tall
+/// shows are copied, with the offset clamped to the length of the history like the
+/// [`PseudoTerminal`] does. The other rows of the history have no cells.
+/// Searching needs the whole history, which is copied with `usize::MAX` for both.
+///
+/// Reading the history needs a mutable parser, since `set_scrollback` is the only way `vt100`
+/// hands it out. `vt100` cannot scroll back further than the height of its screen, so the
+/// screen is grown temporarily when rows further back than that are requested.
+/// The size and the scrollback offset of the parser are restored afterwards.
+///
+/// [`PseudoTerminal`]: crate::widget::PseudoTerminal
+/// [`PseudoTerminal::scrollback`]: crate::widget::PseudoTerminal::scrollback
+/// [`PseudoTerminalState`]: crate::widget::PseudoTerminalState
+///
+/// # Example
+///
+/// ```
+/// use tui_term::{widget::PseudoTerminal, ScrollbackScreen};
+///
+/// let mut parser = vt100::Parser::new(24, 80, 1000);
+/// parser.process(b"a lot of output");
+/// let screen = ScrollbackScreen::new(&mut parser, 10, 24);
+/// let pseudo_term = PseudoTerminal::new(&screen).scrollback(10);
+/// ```
+#[derive(Debug)]
+pub struct ScrollbackScreen<'a> {
+    screen: &'a vt100::Screen,
+    /// A copy of the rows of the screen if the parser is scrolled back,
+    /// since its screen then shows rows of the history.
+    rows: Option<Rows>,
+    history_len: usize,
+    /// The first copied row of the history.
+    history_start: usize,
+    /// The copied rows of the history, the most recent one first.
+    history: Rows,
+}
+
+impl<'a> ScrollbackScreen<'a> {
+    /// Copies the rows of the scrollback history of the parser shown in a view scrolled up
+    /// `scrollback` rows and `rows` rows tall, and borrows its screen.
+    #[must_use]
+    pub fn new(parser: &'a mut vt100::Parser, scrollback: usize, rows: usize) -> Self {
+        let (screen_rows, cols) = parser.screen().size();
+        let offset = parser.screen().scrollback();
+        parser.set_scrollback(usize::MAX);
+        let history_len = parser.screen().scrollback();
+        let end = scrollback.min(history_len);
+        // `vt100` cannot scroll back further than the height of the screen, so the screen
+        // is grown until the oldest requested row fits and shrunk back afterwards.
+        let height = u16::try_from(end).unwrap_or(u16::MAX).max(screen_rows);
+        let end = end.min(usize::from(height));
+        let start = end.saturating_sub(rows);
+        if height > screen_rows {
+            parser.set_size(height, cols);
+        }
+        // Scrolled back `end` rows, the row `end - 1 - row` of the screen shows the row `row`
+        // of the history
+        parser.set_scrollback(end);
+        let history = Rows::read(
+            parser.screen(),
+            cols,
+            (start..end).filter_map(|row| u16::try_from(end - 1 - row).ok()),
+        );
+        parser.set_scrollback(0);
+        if height > screen_rows {
+            parser.set_size(screen_rows, cols);
+        }
+        let rows = (offset > 0).then(|| Rows::read(parser.screen(), cols, 0..screen_rows));
+        parser.set_scrollback(offset);
+        Self {
+            screen: parser.screen(),
+            rows,
+            history_len,
+            history_start: start,
+            history,
+        }
+    }
+
+    /// Returns the screen of the parser.
+    #[inline]
+    #[must_use]
+    pub const fn screen(&self) -> &'a vt100::Screen {
+        self.screen
+    }
+}
+
+/// Rows of cells copied out of a [`vt100::Screen`].
+#[derive(Debug)]
+struct Rows {
+    cells: Vec<Vec<vt100::Cell>>,
+    wrapped: Vec<bool>,
+}
+
+impl Rows {
+    fn read(screen: &vt100::Screen, cols: u16, rows: impl Iterator<Item = u16>) -> Self {
+        let (cells, wrapped) = rows
+            .map(|row| {
+                let cells = (0..cols)
+                    .filter_map(|col| screen.cell(row, col).cloned())
+                    .collect();
+                (cells, screen.row_wrapped(row))
+            })
+            .unzip();
+        Self { cells, wrapped }
+    }
+
+    fn cell(&self, row: usize, col: u16) -> Option<&vt100::Cell> {
+        self.cells.get(row)?.get(usize::from(col))
+    }
+
+    fn wrapped(&self, row: usize) -> bool {
+        self.wrapped.get(row).copied().unwrap_or(false)
+    }
+}
+
+impl Screen for ScrollbackScreen<'_> {
+    type C = vt100::Cell;
+
+    #[inline]
+    fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+        match &self.rows {
+            Some(rows) => rows.cell(usize::from(row), col),
+            None => Screen::cell(self.screen, row, col),
+        }
+    }
+
+    #[inline]
+    fn hide_cursor(&self) -> bool {
+        Screen::hide_cursor(self.screen)
+    }
+
+    #[inline]
+    fn cursor_position(&self) -> (u16, u16) {
+        Screen::cursor_position(self.screen)
+    }
+
+    #[inline]
+    fn scrollback_len(&self) -> usize {
+        self.history_len
+    }
+
+    #[inline]
+    fn scrollback_cell(&self, row: usize, col: u16) -> Option<&Self::C> {
+        self.history.cell(row.checked_sub(self.history_start)?, col)
+    }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        match &self.rows {
+            Some(rows) => rows.wrapped(usize::from(row)),
+            None => Screen::row_wrapped(self.screen, row),
+        }
+    }
+
+    #[inline]
+    fn scrollback_row_wrapped(&self, row: usize) -> bool {
+        row.checked_sub(self.history_start)
+            .is_some_and(|row| self.history.wrapped(row))
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        Screen::size(self.screen)
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        Screen::title(self.screen)
+    }
+
+    #[inline]
+    fn icon_name(&self) -> &str {
+        Screen::icon_name(self.screen)
+    }
+
+    #[inline]
+    fn alternate_screen(&self) -> bool {
+        Screen::alternate_screen(self.screen)
+    }
+
+    #[inline]
+    fn application_cursor(&self) -> bool {
+        Screen::application_cursor(self.screen)
+    }
+
+    #[inline]
+    fn application_keypad(&self) -> bool {
+        Screen::application_keypad(self.screen)
+    }
+
+    #[inline]
+    fn bracketed_paste(&self) -> bool {
+        Screen::bracketed_paste(self.screen)
+    }
+
+    #[inline]
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        Screen::mouse_protocol_mode(self.screen)
+    }
+
+    #[inline]
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        Screen::mouse_protocol_encoding(self.screen)
+    }
 }
 
 impl Cell for vt100::Cell {
@@ -28,36 +296,53 @@ impl Cell for vt100::Cell {
     }
 
     #[inline]
-    fn apply(&self, cell: &mut ratatui::buffer::Cell) {
-        fill_buf_cell(self, cell)
+    fn symbol(&self) -> Cow<'_, str> {
+        let contents = self.contents();
+        if contents.is_empty() {
+            Cow::Borrowed(" ")
+        } else {
+            Cow::Owned(contents)
+        }
     }
-}
 
-#[inline]
-fn fill_buf_cell(screen_cell: &vt100::Cell, buf_cell: &mut ratatui::buffer::Cell) {
-    let fg = screen_cell.fgcolor();
-    let bg = screen_cell.bgcolor();
-    if screen_cell.has_contents() {
-        buf_cell.set_symbol(&screen_cell.contents());
+    #[inline]
+    fn fg(&self) -> ratatui::style::Color {
+        Color::from(self.fgcolor()).into()
     }
-    let fg: Color = fg.into();
-    let bg: Color = bg.into();
-    let mut style = Style::reset();
-    if screen_cell.bold() {
-        style = style.add_modifier(Modifier::BOLD);
+
+    #[inline]
+    fn bg(&self) -> ratatui::style::Color {
+        Color::from(self.bgcolor()).into()
     }
-    if screen_cell.italic() {
-        style = style.add_modifier(Modifier::ITALIC);
+
+    // `vt100` does not keep track of dim, blinking, hidden and crossed out text.
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        let mut modifiers = Modifier::empty();
+        if self.bold() {
+            modifiers |= Modifier::BOLD;
+        }
+        if self.italic() {
+            modifiers |= Modifier::ITALIC;
+        }
+        if self.underline() {
+            modifiers |= Modifier::UNDERLINED;
+        }
+        if self.inverse() {
+            modifiers |= Modifier::REVERSED;
+        }
+        modifiers
     }
-    if screen_cell.underline() {
-        style = style.add_modifier(Modifier::UNDERLINED);
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.is_wide()
     }
-    if screen_cell.inverse() {
-        style = style.add_modifier(Modifier::REVERSED);
+
+    #[inline]
+    fn is_wide_continuation(&self) -> bool {
+        self.is_wide_continuation()
     }
-    buf_cell.set_style(style);
-    buf_cell.set_fg(fg.into());
-    buf_cell.set_bg(bg.into());
 }
 
 /// Represents a foreground or background color for cells.
@@ -150,3 +435,112 @@ impl From<Color> for ratatui::style::Color {
         }
     }
 }
+
+impl From<vt100::MouseProtocolMode> for MouseProtocolMode {
+    #[inline]
+    fn from(value: vt100::MouseProtocolMode) -> Self {
+        match value {
+            vt100::MouseProtocolMode::None => Self::None,
+            vt100::MouseProtocolMode::Press => Self::Press,
+            vt100::MouseProtocolMode::PressRelease => Self::PressRelease,
+            vt100::MouseProtocolMode::ButtonMotion => Self::ButtonMotion,
+            vt100::MouseProtocolMode::AnyMotion => Self::AnyMotion,
+        }
+    }
+}
+
+impl From<vt100::MouseProtocolEncoding> for MouseProtocolEncoding {
+    #[inline]
+    fn from(value: vt100::MouseProtocolEncoding) -> Self {
+        match value {
+            vt100::MouseProtocolEncoding::Default => Self::Default,
+            vt100::MouseProtocolEncoding::Utf8 => Self::Utf8,
+            vt100::MouseProtocolEncoding::Sgr => Self::Sgr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> vt100::Parser {
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        parser.process(b"a wrapped line");
+        for line in 2..=9 {
+            parser.process(format!("\r\nline {line}").as_bytes());
+        }
+        parser
+    }
+
+    fn row(screen: &ScrollbackScreen<'_>, row: usize) -> String {
+        (0..10)
+            .filter_map(|col| screen.scrollback_cell(row, col))
+            .map(|cell| cell.contents())
+            .collect()
+    }
+
+    #[test]
+    fn scrollback_history() {
+        let mut parser = parser();
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        // The history is longer than the screen
+        assert_eq!(screen.scrollback_len(), 7);
+        assert_eq!(row(&screen, 0), "line 6");
+        assert_eq!(row(&screen, 5), "line");
+        assert_eq!(row(&screen, 6), "a wrapped ");
+        assert!(screen.scrollback_row_wrapped(6));
+        assert!(!screen.scrollback_row_wrapped(5));
+        assert!(screen.scrollback_cell(7, 0).is_none());
+        assert_eq!(screen.screen().contents(), "line 7\nline 8\nline 9");
+        assert!(Screen::scrollback_cell(parser.screen(), 0, 0).is_none());
+    }
+
+    #[test]
+    fn history_rows() {
+        let mut parser = parser();
+        let screen = ScrollbackScreen::new(&mut parser, 4, 2);
+        assert_eq!(screen.scrollback_len(), 7);
+        assert_eq!(row(&screen, 1), "");
+        assert_eq!(row(&screen, 2), "line 4");
+        assert_eq!(row(&screen, 3), "line 3");
+        assert_eq!(row(&screen, 4), "");
+        let screen = ScrollbackScreen::new(&mut parser, 10, 2);
+        assert_eq!(row(&screen, 5), "line");
+        assert!(screen.scrollback_row_wrapped(6));
+    }
+
+    #[test]
+    fn scrollback_offset_kept() {
+        let mut parser = parser();
+        parser.set_scrollback(2);
+        let screen = ScrollbackScreen::new(&mut parser, 1, 3);
+        assert_eq!(row(&screen, 0), "line 6");
+        let live: String = (0..10)
+            .filter_map(|col| Screen::cell(&screen, 0, col))
+            .map(vt100::Cell::contents)
+            .collect();
+        assert_eq!(live, "line 7");
+        assert_eq!(parser.screen().scrollback(), 2);
+    }
+
+    #[test]
+    fn parser_unchanged() {
+        let mut untouched = parser();
+        let mut parser = parser();
+        parser.process(b"\x1b[2;3r\x1b[2;4H");
+        untouched.process(b"\x1b[2;3r\x1b[2;4H");
+        let _ = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        assert_eq!(parser.screen().size(), (3, 10));
+        assert_eq!(parser.screen().scrollback(), 0);
+        // The cursor and the scroll region are kept
+        parser.process(b"x\r\n\r\n\r\nend");
+        untouched.process(b"x\r\n\r\n\r\nend");
+        assert_eq!(
+            parser.screen().contents_formatted(),
+            untouched.screen().contents_formatted()
+        );
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        assert_eq!(screen.scrollback_len(), 7);
+    }
+}
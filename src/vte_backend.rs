@@ -0,0 +1,2016 @@
+//! A terminal backend built on the [`vte`] state machine, independent of `vt100`.
+//!
+//! The [`Parser`] keeps its own grid of [`Cell`]s together with a scrollback history,
+//! and tracks attributes that `vt100` drops, like dim, strikethrough, blinking text,
+//! underline styles and hidden text.
+//! Soft-wrapped rows are reflowed when the size of the terminal changes.
+//!
+//! # Example
+//!
+//! ```
+//! use tui_term::{vte_backend::Parser, widget::PseudoTerminal};
+//!
+//! let mut parser = Parser::new(24, 80, 1000);
+//! parser.process(b"\x1b]2;title\x07\x1b[2;9mdim and crossed out");
+//! assert_eq!(parser.screen().title(), "title");
+//! let pseudo_term = PseudoTerminal::new(parser.screen());
+//! ```
+
+use std::{borrow::Cow, collections::VecDeque};
+
+use ratatui::style::Modifier;
+use unicode_width::UnicodeWidthChar;
+
+use crate::widget::{CursorShape, MouseProtocolEncoding, MouseProtocolMode};
+
+/// The maximum number of codepoints in a cell, a character and its combining characters.
+const CODEPOINTS_IN_CELL: usize = 6;
+
+/// A foreground or background color of a [`Cell`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// The default color of the terminal.
+    #[default]
+    Default,
+    /// A color of the 256 color palette.
+    Idx(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl From<Color> for ratatui::style::Color {
+    #[inline]
+    fn from(value: Color) -> Self {
+        match value {
+            Color::Default => Self::Reset,
+            Color::Idx(i) => Self::Indexed(i),
+            Color::Rgb(r, g, b) => Self::Rgb(r, g, b),
+        }
+    }
+}
+
+/// The style of the underline of a [`Cell`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    /// Not underlined.
+    #[default]
+    None,
+    /// A single straight line.
+    Single,
+    /// Two straight lines.
+    Double,
+    /// A wavy line.
+    Curly,
+    /// A dotted line.
+    Dotted,
+    /// A dashed line.
+    Dashed,
+}
+
+/// How the text of a [`Cell`] blinks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Blink {
+    /// The text does not blink.
+    #[default]
+    None,
+    /// The text blinks less than 150 times per minute.
+    Slow,
+    /// The text blinks 150 times per minute or more.
+    Rapid,
+}
+
+/// The attributes that are set by SGR sequences.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+struct Attrs {
+    fg: Color,
+    bg: Color,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: UnderlineStyle,
+    blink: Blink,
+    inverse: bool,
+    hidden: bool,
+    strikethrough: bool,
+}
+
+/// A single cell of a [`Screen`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Cell {
+    contents: String,
+    attrs: Attrs,
+    wide: bool,
+    wide_continuation: bool,
+}
+
+impl Cell {
+    fn set(&mut self, c: char, attrs: Attrs, wide: bool) {
+        self.contents.clear();
+        self.contents.push(c);
+        self.attrs = attrs;
+        self.wide = wide;
+        self.wide_continuation = false;
+    }
+
+    fn append(&mut self, c: char) {
+        if self.contents.chars().count() >= CODEPOINTS_IN_CELL {
+            return;
+        }
+        if self.contents.is_empty() {
+            self.contents.push(' ');
+        }
+        self.contents.push(c);
+    }
+
+    fn clear(&mut self, attrs: Attrs) {
+        self.contents.clear();
+        self.attrs = attrs;
+        self.wide = false;
+        self.wide_continuation = false;
+    }
+
+    /// Whether the cell looks the same as a newly created one.
+    fn is_default(&self) -> bool {
+        self.contents.is_empty() && !self.wide_continuation && self.attrs == Attrs::default()
+    }
+
+    /// Returns the text of the cell.
+    ///
+    /// Contains a single character followed by its combining characters,
+    /// or is empty if nothing was written to the cell.
+    #[inline]
+    #[must_use]
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    /// Returns whether anything was written to the cell.
+    #[inline]
+    #[must_use]
+    pub fn has_contents(&self) -> bool {
+        !self.contents.is_empty()
+    }
+
+    /// Returns the foreground color of the cell.
+    #[inline]
+    #[must_use]
+    pub const fn fgcolor(&self) -> Color {
+        self.attrs.fg
+    }
+
+    /// Returns the background color of the cell.
+    #[inline]
+    #[must_use]
+    pub const fn bgcolor(&self) -> Color {
+        self.attrs.bg
+    }
+
+    /// Returns whether the cell is bold.
+    #[inline]
+    #[must_use]
+    pub const fn bold(&self) -> bool {
+        self.attrs.bold
+    }
+
+    /// Returns whether the cell is dim.
+    #[inline]
+    #[must_use]
+    pub const fn dim(&self) -> bool {
+        self.attrs.dim
+    }
+
+    /// Returns whether the cell is italic.
+    #[inline]
+    #[must_use]
+    pub const fn italic(&self) -> bool {
+        self.attrs.italic
+    }
+
+    /// Returns how the cell is underlined.
+    #[inline]
+    #[must_use]
+    pub const fn underline(&self) -> UnderlineStyle {
+        self.attrs.underline
+    }
+
+    /// Returns how the cell blinks.
+    #[inline]
+    #[must_use]
+    pub const fn blink(&self) -> Blink {
+        self.attrs.blink
+    }
+
+    /// Returns whether the foreground and background colors of the cell are swapped.
+    #[inline]
+    #[must_use]
+    pub const fn inverse(&self) -> bool {
+        self.attrs.inverse
+    }
+
+    /// Returns whether the text of the cell is hidden.
+    #[inline]
+    #[must_use]
+    pub const fn hidden(&self) -> bool {
+        self.attrs.hidden
+    }
+
+    /// Returns whether the cell is crossed out.
+    #[inline]
+    #[must_use]
+    pub const fn strikethrough(&self) -> bool {
+        self.attrs.strikethrough
+    }
+
+    /// Returns whether the cell holds a character that takes up two columns.
+    #[inline]
+    #[must_use]
+    pub const fn is_wide(&self) -> bool {
+        self.wide
+    }
+
+    /// Returns whether the cell is covered by the wide character in the cell before it.
+    #[inline]
+    #[must_use]
+    pub const fn is_wide_continuation(&self) -> bool {
+        self.wide_continuation
+    }
+}
+
+impl crate::widget::Cell for Cell {
+    #[inline]
+    fn has_contents(&self) -> bool {
+        self.has_contents()
+    }
+
+    #[inline]
+    fn symbol(&self) -> Cow<'_, str> {
+        if self.has_contents() {
+            Cow::Borrowed(self.contents())
+        } else {
+            Cow::Borrowed(" ")
+        }
+    }
+
+    #[inline]
+    fn fg(&self) -> ratatui::style::Color {
+        self.attrs.fg.into()
+    }
+
+    #[inline]
+    fn bg(&self) -> ratatui::style::Color {
+        self.attrs.bg.into()
+    }
+
+    #[inline]
+    fn modifiers(&self) -> Modifier {
+        let attrs = &self.attrs;
+        let mut modifiers = Modifier::empty();
+        if attrs.bold {
+            modifiers |= Modifier::BOLD;
+        }
+        if attrs.dim {
+            modifiers |= Modifier::DIM;
+        }
+        if attrs.italic {
+            modifiers |= Modifier::ITALIC;
+        }
+        if attrs.underline != UnderlineStyle::None {
+            modifiers |= Modifier::UNDERLINED;
+        }
+        match attrs.blink {
+            Blink::None => {}
+            Blink::Slow => modifiers |= Modifier::SLOW_BLINK,
+            Blink::Rapid => modifiers |= Modifier::RAPID_BLINK,
+        }
+        if attrs.inverse {
+            modifiers |= Modifier::REVERSED;
+        }
+        if attrs.hidden {
+            modifiers |= Modifier::HIDDEN;
+        }
+        if attrs.strikethrough {
+            modifiers |= Modifier::CROSSED_OUT;
+        }
+        modifiers
+    }
+
+    #[inline]
+    fn is_wide(&self) -> bool {
+        self.is_wide()
+    }
+
+    #[inline]
+    fn is_wide_continuation(&self) -> bool {
+        self.is_wide_continuation()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    cells: Vec<Cell>,
+    wrapped: bool,
+}
+
+impl Row {
+    fn new(width: u16) -> Self {
+        Self {
+            cells: vec![Cell::default(); usize::from(width)],
+            wrapped: false,
+        }
+    }
+
+    fn clear(&mut self, attrs: Attrs) {
+        for cell in &mut self.cells {
+            cell.clear(attrs);
+        }
+        self.wrapped = false;
+    }
+
+    fn is_blank(&self) -> bool {
+        self.cells.iter().all(Cell::is_default)
+    }
+
+    /// Clears the other half of a wide character at `col`, so it can be overwritten.
+    fn clear_wide(&mut self, col: usize) {
+        let Some(cell) = self.cells.get(col) else {
+            return;
+        };
+        let other = if cell.wide {
+            col + 1
+        } else if cell.wide_continuation && col > 0 {
+            col - 1
+        } else {
+            return;
+        };
+        if let Some(other) = self.cells.get_mut(other) {
+            let attrs = other.attrs;
+            other.clear(attrs);
+        }
+    }
+
+    fn erase(&mut self, col: usize, attrs: Attrs) {
+        self.clear_wide(col);
+        self.cells[col].clear(attrs);
+    }
+
+    /// Drops a wide character whose continuation was cut off at the end of the row.
+    fn fix_end(&mut self) {
+        if let Some(last) = self.cells.last_mut() {
+            if last.wide {
+                let attrs = last.attrs;
+                last.clear(attrs);
+            }
+        }
+        self.wrapped = false;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Pos {
+    row: u16,
+    col: u16,
+}
+
+/// The state saved by DECSC and restored by DECRC.
+#[derive(Debug, Default, Clone, Copy)]
+struct SavedCursor {
+    pos: Pos,
+    attrs: Attrs,
+    origin_mode: bool,
+    charsets: [Charset; 2],
+    active_charset: usize,
+}
+
+/// The rows of a screen, with the cursor and the scroll region.
+#[derive(Debug, Clone)]
+struct Grid {
+    rows: Vec<Row>,
+    height: u16,
+    width: u16,
+    /// Rows that scrolled off the top, the most recent one first.
+    scrollback: VecDeque<Row>,
+    scrollback_limit: usize,
+    pos: Pos,
+    /// Set after writing to the last column, the next character wraps to the next row.
+    wrap_pending: bool,
+    scroll_top: u16,
+    scroll_bottom: u16,
+    origin_mode: bool,
+    saved: SavedCursor,
+}
+
+impl Grid {
+    fn new(height: u16, width: u16, scrollback_limit: usize) -> Self {
+        let height = height.max(1);
+        let width = width.max(1);
+        Self {
+            rows: vec![Row::new(width); usize::from(height)],
+            height,
+            width,
+            scrollback: VecDeque::new(),
+            scrollback_limit,
+            pos: Pos::default(),
+            wrap_pending: false,
+            scroll_top: 0,
+            scroll_bottom: height - 1,
+            origin_mode: false,
+            saved: SavedCursor::default(),
+        }
+    }
+
+    fn current_row(&mut self) -> &mut Row {
+        &mut self.rows[usize::from(self.pos.row)]
+    }
+
+    fn in_scroll_region(&self) -> bool {
+        (self.scroll_top..=self.scroll_bottom).contains(&self.pos.row)
+    }
+
+    /// Moves the cursor, relative to the scroll region in origin mode.
+    fn goto(&mut self, row: u16, col: u16) {
+        let (top, bottom) = if self.origin_mode {
+            (self.scroll_top, self.scroll_bottom)
+        } else {
+            (0, self.height - 1)
+        };
+        self.pos.row = row.saturating_add(top).min(bottom);
+        self.pos.col = col.min(self.width - 1);
+        self.wrap_pending = false;
+    }
+
+    fn goto_row(&mut self, row: u16) {
+        let col = self.pos.col;
+        self.goto(row, col);
+    }
+
+    fn goto_col(&mut self, col: u16) {
+        self.pos.col = col.min(self.width - 1);
+        self.wrap_pending = false;
+    }
+
+    fn move_up(&mut self, count: u16) {
+        let top = if self.pos.row >= self.scroll_top {
+            self.scroll_top
+        } else {
+            0
+        };
+        self.pos.row = self.pos.row.saturating_sub(count).max(top);
+        self.wrap_pending = false;
+    }
+
+    fn move_down(&mut self, count: u16) {
+        let bottom = if self.pos.row <= self.scroll_bottom {
+            self.scroll_bottom
+        } else {
+            self.height - 1
+        };
+        self.pos.row = self.pos.row.saturating_add(count).min(bottom);
+        self.wrap_pending = false;
+    }
+
+    fn linefeed(&mut self) {
+        if self.pos.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.pos.row < self.height - 1 {
+            self.pos.row += 1;
+        }
+        self.wrap_pending = false;
+    }
+
+    fn reverse_index(&mut self) {
+        if self.pos.row == self.scroll_top {
+            self.scroll_down(1);
+        } else if self.pos.row > 0 {
+            self.pos.row -= 1;
+        }
+        self.wrap_pending = false;
+    }
+
+    fn scroll_up(&mut self, count: u16) {
+        let top = usize::from(self.scroll_top);
+        let bottom = usize::from(self.scroll_bottom);
+        for _ in 0..count.min(self.scroll_bottom - self.scroll_top + 1) {
+            let row = self.rows.remove(top);
+            self.rows.insert(bottom, Row::new(self.width));
+            if top == 0 && self.scrollback_limit > 0 {
+                self.scrollback.push_front(row);
+                self.scrollback.truncate(self.scrollback_limit);
+            }
+        }
+    }
+
+    fn scroll_down(&mut self, count: u16) {
+        let top = usize::from(self.scroll_top);
+        let bottom = usize::from(self.scroll_bottom);
+        for _ in 0..count.min(self.scroll_bottom - self.scroll_top + 1) {
+            self.rows.remove(bottom);
+            self.rows.insert(top, Row::new(self.width));
+        }
+    }
+
+    fn set_scroll_region(&mut self, top: u16, bottom: u16) {
+        let bottom = bottom.min(self.height - 1);
+        if top < bottom {
+            self.scroll_top = top;
+            self.scroll_bottom = bottom;
+            self.goto(0, 0);
+        }
+    }
+
+    fn reset_scroll_region(&mut self) {
+        self.scroll_top = 0;
+        self.scroll_bottom = self.height - 1;
+    }
+
+    fn insert_lines(&mut self, count: u16) {
+        if !self.in_scroll_region() {
+            return;
+        }
+        let row = usize::from(self.pos.row);
+        let bottom = usize::from(self.scroll_bottom);
+        for _ in 0..count.min(self.scroll_bottom - self.pos.row + 1) {
+            self.rows.remove(bottom);
+            self.rows.insert(row, Row::new(self.width));
+        }
+        self.goto_col(0);
+    }
+
+    fn delete_lines(&mut self, count: u16) {
+        if !self.in_scroll_region() {
+            return;
+        }
+        let row = usize::from(self.pos.row);
+        let bottom = usize::from(self.scroll_bottom);
+        for _ in 0..count.min(self.scroll_bottom - self.pos.row + 1) {
+            self.rows.remove(row);
+            self.rows.insert(bottom, Row::new(self.width));
+        }
+        self.goto_col(0);
+    }
+
+    fn insert_cells(&mut self, count: u16, attrs: Attrs) {
+        let col = usize::from(self.pos.col);
+        let width = usize::from(self.width);
+        let count = usize::from(count).min(width - col);
+        let row = self.current_row();
+        row.clear_wide(col);
+        for _ in 0..count {
+            row.cells.insert(col, Cell::default());
+            row.cells[col].clear(attrs);
+        }
+        row.cells.truncate(width);
+        row.fix_end();
+        self.wrap_pending = false;
+    }
+
+    fn delete_cells(&mut self, count: u16, attrs: Attrs) {
+        let col = usize::from(self.pos.col);
+        let width = usize::from(self.width);
+        let count = usize::from(count).min(width - col);
+        let row = self.current_row();
+        row.clear_wide(col);
+        row.clear_wide(col + count - 1);
+        row.cells.drain(col..col + count);
+        let mut blank = Cell::default();
+        blank.clear(attrs);
+        row.cells.resize(width, blank);
+        row.wrapped = false;
+        self.wrap_pending = false;
+    }
+
+    fn erase_cells(&mut self, count: u16, attrs: Attrs) {
+        let col = self.pos.col;
+        let end = col.saturating_add(count).min(self.width);
+        let row = self.current_row();
+        for col in col..end {
+            row.erase(usize::from(col), attrs);
+        }
+        if end == self.width {
+            self.current_row().wrapped = false;
+        }
+        self.wrap_pending = false;
+    }
+
+    /// Erases the whole current row (`2`), the part before the cursor (`1`),
+    /// or the part from the cursor on (`0`).
+    fn erase_line(&mut self, mode: u16, attrs: Attrs) {
+        let col = usize::from(self.pos.col);
+        let width = usize::from(self.width);
+        let row = self.current_row();
+        let range = match mode {
+            0 => col..width,
+            1 => 0..col + 1,
+            2 => 0..width,
+            _ => return,
+        };
+        if range.end == width {
+            row.wrapped = false;
+        }
+        for col in range {
+            row.erase(col, attrs);
+        }
+        self.wrap_pending = false;
+    }
+
+    /// Erases the whole screen (`2`), the part before the cursor (`1`),
+    /// the part from the cursor on (`0`) or the scrollback history (`3`).
+    fn erase_display(&mut self, mode: u16, attrs: Attrs) {
+        let row = usize::from(self.pos.row);
+        let rows = match mode {
+            0 => row + 1..self.rows.len(),
+            1 => 0..row,
+            2 => 0..self.rows.len(),
+            3 => {
+                self.scrollback.clear();
+                return;
+            }
+            _ => return,
+        };
+        for row in &mut self.rows[rows] {
+            row.clear(attrs);
+        }
+        if mode != 2 {
+            self.erase_line(mode, attrs);
+        }
+        self.wrap_pending = false;
+    }
+
+    /// Changes the size of the grid, without moving any content between rows.
+    fn resize(&mut self, height: u16, width: u16) {
+        let height = height.max(1);
+        let width = width.max(1);
+        for row in &mut self.rows {
+            row.cells.resize(usize::from(width), Cell::default());
+            row.fix_end();
+        }
+        self.rows.resize(usize::from(height), Row::new(width));
+        self.set_size(height, width);
+    }
+
+    /// Changes the size of the grid and rewraps soft-wrapped rows to the new width.
+    ///
+    /// Rows that no longer fit on the screen move into the scrollback history,
+    /// and rows are taken back from the history when there is room.
+    fn reflow(&mut self, height: u16, width: u16) {
+        let height = height.max(1);
+        let width = width.max(1);
+
+        // Blank rows below the cursor are dropped instead of being pushed into the history
+        let last_row = self
+            .rows
+            .iter()
+            .rposition(|row| !row.is_blank())
+            .unwrap_or(0)
+            .max(usize::from(self.pos.row));
+        let mut rows: Vec<Row> = self.scrollback.drain(..).rev().collect();
+        let cursor_row = rows.len() + usize::from(self.pos.row);
+        rows.extend(self.rows.drain(..=last_row));
+
+        // Join the soft-wrapped rows into logical lines
+        let mut lines: Vec<Vec<Cell>> = Vec::new();
+        let mut cursor = (0, 0);
+        let mut line = Vec::new();
+        let last = rows.len() - 1;
+        for (index, row) in rows.into_iter().enumerate() {
+            if index == cursor_row {
+                cursor = (lines.len(), line.len() + usize::from(self.pos.col));
+            }
+            line.extend(row.cells);
+            if !row.wrapped || index == last {
+                while line.last().is_some_and(Cell::is_default) {
+                    line.pop();
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+        }
+
+        // Wrap the lines again at the new width
+        let width_cells = usize::from(width);
+        let mut rows = Vec::new();
+        let mut cursor_pos = (0, 0);
+        for (index, line) in lines.into_iter().enumerate() {
+            let len = line.len();
+            let mut row = Row {
+                cells: Vec::with_capacity(width_cells),
+                wrapped: false,
+            };
+            for (offset, cell) in line.into_iter().enumerate() {
+                let cell_width = if cell.wide { 2 } else { 1 };
+                if !cell.wide_continuation && row.cells.len() + cell_width > width_cells {
+                    row.cells.resize(width_cells, Cell::default());
+                    row.wrapped = true;
+                    rows.push(row);
+                    row = Row {
+                        cells: Vec::with_capacity(width_cells),
+                        wrapped: false,
+                    };
+                }
+                if (index, offset) == cursor {
+                    cursor_pos = (rows.len(), row.cells.len());
+                }
+                row.cells.push(cell);
+            }
+            if index == cursor.0 && cursor.1 >= len {
+                cursor_pos = (rows.len(), row.cells.len() + cursor.1 - len);
+            }
+            row.cells.resize(width_cells, Cell::default());
+            rows.push(row);
+        }
+
+        // Fill the screen from the bottom, keeping the cursor on it
+        let screen_start = rows
+            .len()
+            .saturating_sub(usize::from(height))
+            .min(cursor_pos.0);
+        let mut screen = rows.split_off(screen_start);
+        screen.truncate(usize::from(height));
+        screen.resize(usize::from(height), Row::new(width));
+        for row in rows {
+            self.scrollback.push_front(row);
+        }
+        self.scrollback.truncate(self.scrollback_limit);
+        self.rows = screen;
+        self.pos = Pos {
+            row: u16::try_from(cursor_pos.0 - screen_start).unwrap_or(height - 1),
+            col: u16::try_from(cursor_pos.1)
+                .unwrap_or(u16::MAX)
+                .min(width - 1),
+        };
+        self.set_size(height, width);
+    }
+
+    fn set_size(&mut self, height: u16, width: u16) {
+        self.height = height;
+        self.width = width;
+        self.reset_scroll_region();
+        self.pos.row = self.pos.row.min(height - 1);
+        self.pos.col = self.pos.col.min(width - 1);
+        self.saved.pos.row = self.saved.pos.row.min(height - 1);
+        self.saved.pos.col = self.saved.pos.col.min(width - 1);
+        self.wrap_pending = false;
+    }
+}
+
+/// A character set that can be designated as G0 or G1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    #[default]
+    Ascii,
+    /// The DEC special graphics set, used for drawing lines.
+    DecSpecialGraphics,
+}
+
+impl Charset {
+    fn map(self, c: char) -> char {
+        if self == Self::Ascii {
+            return c;
+        }
+        match c {
+            '_' => ' ',
+            '`' => '◆',
+            'a' => '▒',
+            'b' => '␉',
+            'c' => '␌',
+            'd' => '␍',
+            'e' => '␊',
+            'f' => '°',
+            'g' => '±',
+            'h' => '␤',
+            'i' => '␋',
+            'j' => '┘',
+            'k' => '┐',
+            'l' => '┌',
+            'm' => '└',
+            'n' => '┼',
+            'o' => '⎺',
+            'p' => '⎻',
+            'q' => '─',
+            'r' => '⎼',
+            's' => '⎽',
+            't' => '├',
+            'u' => '┤',
+            'v' => '┴',
+            'w' => '┬',
+            'x' => '│',
+            'y' => '≤',
+            'z' => '≥',
+            '{' => 'π',
+            '|' => '≠',
+            '}' => '£',
+            '~' => '·',
+            c => c,
+        }
+    }
+}
+
+/// The state of the terminal, which is updated by a [`Parser`].
+#[derive(Debug, Clone)]
+pub struct Screen {
+    grid: Grid,
+    alternate_grid: Grid,
+    alternate_screen: bool,
+    attrs: Attrs,
+    tabs: Vec<bool>,
+    charsets: [Charset; 2],
+    active_charset: usize,
+    title: String,
+    icon_name: String,
+    hide_cursor: bool,
+    application_cursor: bool,
+    application_keypad: bool,
+    bracketed_paste: bool,
+    mouse_protocol_mode: MouseProtocolMode,
+    mouse_protocol_encoding: MouseProtocolEncoding,
+    cursor_shape: Option<CursorShape>,
+    autowrap: bool,
+    insert_mode: bool,
+    newline_mode: bool,
+}
+
+impl Screen {
+    fn new(rows: u16, cols: u16, scrollback_len: usize) -> Self {
+        let grid = Grid::new(rows, cols, scrollback_len);
+        Self {
+            alternate_grid: Grid::new(rows, cols, 0),
+            tabs: default_tabs(grid.width),
+            grid,
+            alternate_screen: false,
+            attrs: Attrs::default(),
+            charsets: [Charset::Ascii; 2],
+            active_charset: 0,
+            title: String::new(),
+            icon_name: String::new(),
+            hide_cursor: false,
+            application_cursor: false,
+            application_keypad: false,
+            bracketed_paste: false,
+            mouse_protocol_mode: MouseProtocolMode::None,
+            mouse_protocol_encoding: MouseProtocolEncoding::Default,
+            cursor_shape: None,
+            autowrap: true,
+            insert_mode: false,
+            newline_mode: false,
+        }
+    }
+
+    fn grid(&self) -> &Grid {
+        if self.alternate_screen {
+            &self.alternate_grid
+        } else {
+            &self.grid
+        }
+    }
+
+    fn grid_mut(&mut self) -> &mut Grid {
+        if self.alternate_screen {
+            &mut self.alternate_grid
+        } else {
+            &mut self.grid
+        }
+    }
+
+    fn set_size(&mut self, rows: u16, cols: u16) {
+        self.grid.reflow(rows, cols);
+        self.alternate_grid.resize(rows, cols);
+        // Tab stops are kept, new columns get the default ones
+        let tabs = default_tabs(self.grid.width);
+        let len = self.tabs.len();
+        self.tabs.resize(tabs.len(), false);
+        if len < tabs.len() {
+            self.tabs[len..].copy_from_slice(&tabs[len..]);
+        }
+    }
+
+    /// Returns the size of the screen as (rows, columns).
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> (u16, u16) {
+        let grid = self.grid();
+        (grid.height, grid.width)
+    }
+
+    /// Returns the cell at the given location if it exists.
+    #[inline]
+    #[must_use]
+    pub fn cell(&self, row: u16, col: u16) -> Option<&Cell> {
+        self.grid()
+            .rows
+            .get(usize::from(row))?
+            .cells
+            .get(usize::from(col))
+    }
+
+    /// Returns whether the given row is soft-wrapped and continues on the next row.
+    #[inline]
+    #[must_use]
+    pub fn row_wrapped(&self, row: u16) -> bool {
+        self.grid()
+            .rows
+            .get(usize::from(row))
+            .is_some_and(|row| row.wrapped)
+    }
+
+    /// Returns the number of rows in the scrollback history.
+    ///
+    /// The alternate screen has no history.
+    #[inline]
+    #[must_use]
+    pub fn scrollback_len(&self) -> usize {
+        self.grid().scrollback.len()
+    }
+
+    /// Returns the cell at the given location in the scrollback history if it exists.
+    ///
+    /// Row `0` is the most recent row that scrolled off the top of the screen.
+    #[inline]
+    #[must_use]
+    pub fn scrollback_cell(&self, row: usize, col: u16) -> Option<&Cell> {
+        self.grid().scrollback.get(row)?.cells.get(usize::from(col))
+    }
+
+    /// Returns whether the given row of the scrollback history is soft-wrapped.
+    #[inline]
+    #[must_use]
+    pub fn scrollback_row_wrapped(&self, row: usize) -> bool {
+        self.grid()
+            .scrollback
+            .get(row)
+            .is_some_and(|row| row.wrapped)
+    }
+
+    /// Returns the cursor position as (row, column).
+    #[inline]
+    #[must_use]
+    pub fn cursor_position(&self) -> (u16, u16) {
+        let pos = self.grid().pos;
+        (pos.row, pos.col)
+    }
+
+    /// Returns whether the cursor was hidden by the application.
+    #[inline]
+    #[must_use]
+    pub const fn hide_cursor(&self) -> bool {
+        self.hide_cursor
+    }
+
+    /// Returns the cursor shape requested by the application,
+    /// or `None` if the terminal's default shape should be used.
+    #[inline]
+    #[must_use]
+    pub const fn cursor_shape(&self) -> Option<CursorShape> {
+        self.cursor_shape
+    }
+
+    /// Returns the window title set by the application.
+    #[inline]
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the icon name set by the application.
+    #[inline]
+    #[must_use]
+    pub fn icon_name(&self) -> &str {
+        &self.icon_name
+    }
+
+    /// Returns whether the alternate screen is active.
+    #[inline]
+    #[must_use]
+    pub const fn alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
+    /// Returns whether the application requested application cursor key mode.
+    #[inline]
+    #[must_use]
+    pub const fn application_cursor(&self) -> bool {
+        self.application_cursor
+    }
+
+    /// Returns whether the application requested application keypad mode.
+    #[inline]
+    #[must_use]
+    pub const fn application_keypad(&self) -> bool {
+        self.application_keypad
+    }
+
+    /// Returns whether the application requested bracketed paste mode.
+    #[inline]
+    #[must_use]
+    pub const fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    /// Returns which mouse events the application requested to be reported.
+    #[inline]
+    #[must_use]
+    pub const fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        self.mouse_protocol_mode
+    }
+
+    /// Returns how the application requested mouse events to be encoded.
+    #[inline]
+    #[must_use]
+    pub const fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        self.mouse_protocol_encoding
+    }
+
+    /// Returns the text of the screen, with trailing whitespace trimmed from every row.
+    #[must_use]
+    pub fn contents(&self) -> String {
+        let mut contents = String::new();
+        for (index, row) in self.grid().rows.iter().enumerate() {
+            if index > 0 {
+                contents.push('\n');
+            }
+            let start = contents.len();
+            for cell in &row.cells {
+                if cell.wide_continuation {
+                    continue;
+                }
+                contents.push_str(if cell.has_contents() {
+                    cell.contents()
+                } else {
+                    " "
+                });
+            }
+            contents.truncate(start + contents[start..].trim_end().len());
+        }
+        contents.truncate(contents.trim_end().len());
+        contents
+    }
+
+    fn text(&mut self, c: char) {
+        let c = self.charsets[self.active_charset].map(c);
+        let width = match c.width() {
+            Some(width) => u16::try_from(width).unwrap_or(1),
+            // Control characters are not drawn
+            None if u32::from(c) < 256 => return,
+            None => 1,
+        };
+        if width == 0 {
+            self.combine(c);
+            return;
+        }
+
+        let attrs = self.attrs;
+        let autowrap = self.autowrap;
+        let insert_mode = self.insert_mode;
+        let grid = self.grid_mut();
+        if width > grid.width {
+            return;
+        }
+        if grid.wrap_pending || grid.pos.col + width > grid.width {
+            if autowrap {
+                grid.current_row().wrapped = true;
+                grid.pos.col = 0;
+                grid.linefeed();
+            } else {
+                grid.pos.col = grid.width - width;
+            }
+        }
+        if insert_mode {
+            grid.insert_cells(width, Attrs::default());
+        }
+
+        let col = usize::from(grid.pos.col);
+        let row = grid.current_row();
+        row.clear_wide(col);
+        if width > 1 {
+            row.clear_wide(col + 1);
+        }
+        row.cells[col].set(c, attrs, width > 1);
+        if width > 1 {
+            let next = &mut row.cells[col + 1];
+            next.clear(attrs);
+            next.wide_continuation = true;
+        }
+
+        if grid.pos.col + width >= grid.width {
+            grid.pos.col = grid.width - 1;
+            grid.wrap_pending = autowrap;
+        } else {
+            grid.pos.col += width;
+        }
+    }
+
+    /// Adds a combining character to the character that was written last.
+    fn combine(&mut self, c: char) {
+        let grid = self.grid_mut();
+        let Pos { mut row, mut col } = grid.pos;
+        if !grid.wrap_pending {
+            if col > 0 {
+                col -= 1;
+            } else if row > 0 && grid.rows[usize::from(row) - 1].wrapped {
+                row -= 1;
+                col = grid.width - 1;
+            } else {
+                return;
+            }
+        }
+        let row = &mut grid.rows[usize::from(row)];
+        let mut col = usize::from(col);
+        if row.cells[col].wide_continuation && col > 0 {
+            col -= 1;
+        }
+        row.cells[col].append(c);
+    }
+
+    fn tab(&mut self, count: u16) {
+        let grid = self.grid();
+        let mut col = usize::from(grid.pos.col);
+        let last = usize::from(grid.width - 1);
+        for _ in 0..count {
+            col = (col + 1..last)
+                .find(|col| self.tabs.get(*col).copied().unwrap_or(false))
+                .unwrap_or(last);
+        }
+        let col = u16::try_from(col).unwrap_or(u16::MAX);
+        self.grid_mut().goto_col(col);
+    }
+
+    fn back_tab(&mut self, count: u16) {
+        let mut col = usize::from(self.grid().pos.col);
+        for _ in 0..count {
+            col = (1..col)
+                .rev()
+                .find(|col| self.tabs.get(*col).copied().unwrap_or(false))
+                .unwrap_or(0);
+        }
+        let col = u16::try_from(col).unwrap_or(u16::MAX);
+        self.grid_mut().goto_col(col);
+    }
+
+    fn save_cursor(&mut self) {
+        let saved = SavedCursor {
+            pos: self.grid().pos,
+            attrs: self.attrs,
+            origin_mode: self.grid().origin_mode,
+            charsets: self.charsets,
+            active_charset: self.active_charset,
+        };
+        self.grid_mut().saved = saved;
+    }
+
+    fn restore_cursor(&mut self) {
+        let saved = self.grid().saved;
+        self.attrs = saved.attrs;
+        self.charsets = saved.charsets;
+        self.active_charset = saved.active_charset;
+        let grid = self.grid_mut();
+        grid.origin_mode = saved.origin_mode;
+        grid.pos.row = saved.pos.row.min(grid.height - 1);
+        grid.pos.col = saved.pos.col.min(grid.width - 1);
+        grid.wrap_pending = false;
+    }
+
+    fn enter_alternate_screen(&mut self, clear: bool) {
+        if clear {
+            let (rows, cols) = (self.grid.height, self.grid.width);
+            self.alternate_grid = Grid::new(rows, cols, 0);
+        }
+        self.alternate_screen = true;
+    }
+
+    /// Resets the screen like RIS, keeping the size, history and title.
+    fn reset(&mut self) {
+        let mut screen = Self::new(
+            self.grid.height,
+            self.grid.width,
+            self.grid.scrollback_limit,
+        );
+        std::mem::swap(&mut screen.grid.scrollback, &mut self.grid.scrollback);
+        std::mem::swap(&mut screen.title, &mut self.title);
+        std::mem::swap(&mut screen.icon_name, &mut self.icon_name);
+        *self = screen;
+    }
+
+    /// Resets modes and attributes like DECSTR, without touching the contents.
+    fn soft_reset(&mut self) {
+        self.attrs = Attrs::default();
+        self.charsets = [Charset::Ascii; 2];
+        self.active_charset = 0;
+        self.hide_cursor = false;
+        self.application_cursor = false;
+        self.application_keypad = false;
+        self.autowrap = true;
+        self.insert_mode = false;
+        let grid = self.grid_mut();
+        grid.origin_mode = false;
+        grid.reset_scroll_region();
+        grid.saved = SavedCursor::default();
+    }
+
+    /// Fills the screen with `E` like DECALN.
+    fn screen_alignment(&mut self) {
+        let grid = self.grid_mut();
+        for row in &mut grid.rows {
+            for cell in &mut row.cells {
+                cell.set('E', Attrs::default(), false);
+            }
+            row.wrapped = false;
+        }
+        grid.reset_scroll_region();
+        grid.origin_mode = false;
+        grid.goto(0, 0);
+    }
+
+    fn set_mode(&mut self, params: &vte::Params, value: bool) {
+        for param in params {
+            match param {
+                [4] => self.insert_mode = value,
+                [20] => self.newline_mode = value,
+                _ => {}
+            }
+        }
+    }
+
+    /// Resetting a mouse mode only has an effect if it is the active one.
+    fn set_mouse_protocol_mode(&mut self, mode: MouseProtocolMode, value: bool) {
+        if value {
+            self.mouse_protocol_mode = mode;
+        } else if self.mouse_protocol_mode == mode {
+            self.mouse_protocol_mode = MouseProtocolMode::None;
+        }
+    }
+
+    fn set_mouse_protocol_encoding(&mut self, encoding: MouseProtocolEncoding, value: bool) {
+        if value {
+            self.mouse_protocol_encoding = encoding;
+        } else if self.mouse_protocol_encoding == encoding {
+            self.mouse_protocol_encoding = MouseProtocolEncoding::Default;
+        }
+    }
+
+    fn set_private_mode(&mut self, params: &vte::Params, value: bool) {
+        for param in params {
+            match param {
+                [1] => self.application_cursor = value,
+                // The size is controlled by the application that embeds the terminal,
+                // so switching between 80 and 132 columns only clears the screen
+                [3] => {
+                    let grid = self.grid_mut();
+                    grid.erase_display(2, Attrs::default());
+                    grid.reset_scroll_region();
+                    grid.goto(0, 0);
+                }
+                [6] => {
+                    let grid = self.grid_mut();
+                    grid.origin_mode = value;
+                    grid.goto(0, 0);
+                }
+                [7] => {
+                    self.autowrap = value;
+                    self.grid_mut().wrap_pending = false;
+                }
+                [25] => self.hide_cursor = !value,
+                [47] => {
+                    if value {
+                        self.enter_alternate_screen(false);
+                    } else {
+                        self.alternate_screen = false;
+                    }
+                }
+                [1047] => {
+                    if value {
+                        self.enter_alternate_screen(true);
+                    } else {
+                        self.alternate_screen = false;
+                    }
+                }
+                [1048] => {
+                    if value {
+                        self.save_cursor();
+                    } else {
+                        self.restore_cursor();
+                    }
+                }
+                [1049] => {
+                    if value {
+                        if !self.alternate_screen {
+                            self.save_cursor();
+                        }
+                        self.enter_alternate_screen(true);
+                    } else if self.alternate_screen {
+                        self.alternate_screen = false;
+                        self.restore_cursor();
+                    }
+                }
+                [9] => self.set_mouse_protocol_mode(MouseProtocolMode::Press, value),
+                [1000] => self.set_mouse_protocol_mode(MouseProtocolMode::PressRelease, value),
+                [1002] => self.set_mouse_protocol_mode(MouseProtocolMode::ButtonMotion, value),
+                [1003] => self.set_mouse_protocol_mode(MouseProtocolMode::AnyMotion, value),
+                [1005] => self.set_mouse_protocol_encoding(MouseProtocolEncoding::Utf8, value),
+                [1006] => self.set_mouse_protocol_encoding(MouseProtocolEncoding::Sgr, value),
+                [2004] => self.bracketed_paste = value,
+                _ => {}
+            }
+        }
+    }
+
+    fn sgr(&mut self, params: &vte::Params) {
+        if params.is_empty() {
+            self.attrs = Attrs::default();
+            return;
+        }
+        let mut iter = params.iter();
+        while let Some(param) = iter.next() {
+            let attrs = &mut self.attrs;
+            match param {
+                [0] => *attrs = Attrs::default(),
+                [1] => attrs.bold = true,
+                [2] => attrs.dim = true,
+                [3] => attrs.italic = true,
+                [4] => attrs.underline = UnderlineStyle::Single,
+                [4, style, ..] => {
+                    attrs.underline = match style {
+                        0 => UnderlineStyle::None,
+                        2 => UnderlineStyle::Double,
+                        3 => UnderlineStyle::Curly,
+                        4 => UnderlineStyle::Dotted,
+                        5 => UnderlineStyle::Dashed,
+                        _ => UnderlineStyle::Single,
+                    };
+                }
+                [5] => attrs.blink = Blink::Slow,
+                [6] => attrs.blink = Blink::Rapid,
+                [7] => attrs.inverse = true,
+                [8] => attrs.hidden = true,
+                [9] => attrs.strikethrough = true,
+                [21] => attrs.underline = UnderlineStyle::Double,
+                [22] => {
+                    attrs.bold = false;
+                    attrs.dim = false;
+                }
+                [23] => attrs.italic = false,
+                [24] => attrs.underline = UnderlineStyle::None,
+                [25] => attrs.blink = Blink::None,
+                [27] => attrs.inverse = false,
+                [28] => attrs.hidden = false,
+                [29] => attrs.strikethrough = false,
+                [n @ 30..=37] => attrs.fg = Color::Idx(color_index(n - 30)),
+                [38, ..] => {
+                    if let Some(color) = extended_color(param, &mut iter) {
+                        attrs.fg = color;
+                    }
+                }
+                [39] => attrs.fg = Color::Default,
+                [n @ 40..=47] => attrs.bg = Color::Idx(color_index(n - 40)),
+                [48, ..] => {
+                    if let Some(color) = extended_color(param, &mut iter) {
+                        attrs.bg = color;
+                    }
+                }
+                [49] => attrs.bg = Color::Default,
+                // The underline color is not supported, but its parameters are skipped
+                [58, ..] => {
+                    extended_color(param, &mut iter);
+                }
+                [n @ 90..=97] => attrs.fg = Color::Idx(color_index(n - 82)),
+                [n @ 100..=107] => attrs.bg = Color::Idx(color_index(n - 92)),
+                _ => {}
+            }
+        }
+    }
+
+    fn osc(&mut self, params: &[&[u8]]) {
+        let Some((kind, text)) = params.split_first() else {
+            return;
+        };
+        // The text itself may contain separators
+        let text = text.join(&b';');
+        let Ok(text) = String::from_utf8(text) else {
+            return;
+        };
+        match *kind {
+            b"0" => {
+                self.icon_name.clone_from(&text);
+                self.title = text;
+            }
+            b"1" => self.icon_name = text,
+            b"2" => self.title = text,
+            _ => {}
+        }
+    }
+}
+
+impl vte::Perform for Screen {
+    fn print(&mut self, c: char) {
+        self.text(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            // BS
+            8 => {
+                let grid = self.grid_mut();
+                let col = grid.pos.col.saturating_sub(1);
+                grid.goto_col(col);
+            }
+            // HT
+            9 => self.tab(1),
+            // LF, VT, FF
+            10..=12 => {
+                let newline_mode = self.newline_mode;
+                let grid = self.grid_mut();
+                grid.linefeed();
+                if newline_mode {
+                    grid.goto_col(0);
+                }
+            }
+            // CR
+            13 => self.grid_mut().goto_col(0),
+            // SO
+            14 => self.active_charset = 1,
+            // SI
+            15 => self.active_charset = 0,
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        if ignore {
+            return;
+        }
+        match (intermediates, byte) {
+            ([], b'7') => self.save_cursor(),
+            ([], b'8') => self.restore_cursor(),
+            ([], b'=') => self.application_keypad = true,
+            ([], b'>') => self.application_keypad = false,
+            // IND
+            ([], b'D') => self.grid_mut().linefeed(),
+            // NEL
+            ([], b'E') => {
+                let grid = self.grid_mut();
+                grid.linefeed();
+                grid.goto_col(0);
+            }
+            // HTS
+            ([], b'H') => {
+                let col = usize::from(self.grid().pos.col);
+                if let Some(tab) = self.tabs.get_mut(col) {
+                    *tab = true;
+                }
+            }
+            // RI
+            ([], b'M') => self.grid_mut().reverse_index(),
+            // RIS
+            ([], b'c') => self.reset(),
+            ([b'#'], b'8') => self.screen_alignment(),
+            ([b'('], charset) => self.charsets[0] = designate_charset(charset),
+            ([b')'], charset) => self.charsets[1] = designate_charset(charset),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+    ) {
+        if ignore {
+            return;
+        }
+        let attrs = self.attrs;
+        let first = param(params, 0, 1);
+        match (intermediates, action) {
+            // ICH
+            ([], '@') => self.grid_mut().insert_cells(first, attrs),
+            // CUU
+            ([], 'A') => self.grid_mut().move_up(first),
+            // CUD, VPR
+            ([], 'B' | 'e') => self.grid_mut().move_down(first),
+            // CUF, HPR
+            ([], 'C' | 'a') => {
+                let grid = self.grid_mut();
+                let col = grid.pos.col.saturating_add(first);
+                grid.goto_col(col);
+            }
+            // CUB
+            ([], 'D') => {
+                let grid = self.grid_mut();
+                let col = grid.pos.col.saturating_sub(first);
+                grid.goto_col(col);
+            }
+            // CNL
+            ([], 'E') => {
+                let grid = self.grid_mut();
+                grid.move_down(first);
+                grid.goto_col(0);
+            }
+            // CPL
+            ([], 'F') => {
+                let grid = self.grid_mut();
+                grid.move_up(first);
+                grid.goto_col(0);
+            }
+            // CHA, HPA
+            ([], 'G' | '`') => self.grid_mut().goto_col(first - 1),
+            // CUP, HVP
+            ([], 'H' | 'f') => {
+                let col = param(params, 1, 1);
+                self.grid_mut().goto(first - 1, col - 1);
+            }
+            // CHT
+            ([], 'I') => self.tab(first),
+            // ED
+            ([] | [b'?'], 'J') => self.grid_mut().erase_display(param(params, 0, 0), attrs),
+            // EL
+            ([] | [b'?'], 'K') => self.grid_mut().erase_line(param(params, 0, 0), attrs),
+            // IL
+            ([], 'L') => self.grid_mut().insert_lines(first),
+            // DL
+            ([], 'M') => self.grid_mut().delete_lines(first),
+            // DCH
+            ([], 'P') => self.grid_mut().delete_cells(first, attrs),
+            // SU
+            ([], 'S') => self.grid_mut().scroll_up(first),
+            // SD
+            ([], 'T') => self.grid_mut().scroll_down(first),
+            // ECH
+            ([], 'X') => self.grid_mut().erase_cells(first, attrs),
+            // CBT
+            ([], 'Z') => self.back_tab(first),
+            // VPA
+            ([], 'd') => self.grid_mut().goto_row(first - 1),
+            // TBC
+            ([], 'g') => match param(params, 0, 0) {
+                0 => {
+                    let col = usize::from(self.grid().pos.col);
+                    if let Some(tab) = self.tabs.get_mut(col) {
+                        *tab = false;
+                    }
+                }
+                3 => self.tabs.fill(false),
+                _ => {}
+            },
+            ([], 'h') => self.set_mode(params, true),
+            ([], 'l') => self.set_mode(params, false),
+            ([b'?'], 'h') => self.set_private_mode(params, true),
+            ([b'?'], 'l') => self.set_private_mode(params, false),
+            ([], 'm') => self.sgr(params),
+            // DECSTBM
+            ([], 'r') => {
+                let grid = self.grid_mut();
+                let bottom = param(params, 1, grid.height);
+                grid.set_scroll_region(first - 1, bottom - 1);
+            }
+            // SCOSC
+            ([], 's') => self.save_cursor(),
+            // SCORC
+            ([], 'u') => self.restore_cursor(),
+            // DECSTR
+            ([b'!'], 'p') => self.soft_reset(),
+            // DECSCUSR, blinking is not tracked
+            ([b' '], 'q') => {
+                self.cursor_shape = match param(params, 0, 0) {
+                    1 | 2 => Some(CursorShape::Block),
+                    3 | 4 => Some(CursorShape::Underline),
+                    5 | 6 => Some(CursorShape::Bar),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        self.osc(params);
+    }
+}
+
+impl crate::widget::Screen for Screen {
+    type C = Cell;
+
+    #[inline]
+    fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+        self.cell(row, col)
+    }
+
+    #[inline]
+    fn hide_cursor(&self) -> bool {
+        self.hide_cursor()
+    }
+
+    #[inline]
+    fn cursor_position(&self) -> (u16, u16) {
+        self.cursor_position()
+    }
+
+    #[inline]
+    fn scrollback_len(&self) -> usize {
+        self.scrollback_len()
+    }
+
+    #[inline]
+    fn scrollback_cell(&self, row: usize, col: u16) -> Option<&Self::C> {
+        self.scrollback_cell(row, col)
+    }
+
+    #[inline]
+    fn row_wrapped(&self, row: u16) -> bool {
+        self.row_wrapped(row)
+    }
+
+    #[inline]
+    fn scrollback_row_wrapped(&self, row: usize) -> bool {
+        self.scrollback_row_wrapped(row)
+    }
+
+    #[inline]
+    fn size(&self) -> (u16, u16) {
+        self.size()
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        self.title()
+    }
+
+    #[inline]
+    fn icon_name(&self) -> &str {
+        self.icon_name()
+    }
+
+    #[inline]
+    fn alternate_screen(&self) -> bool {
+        self.alternate_screen()
+    }
+
+    #[inline]
+    fn application_cursor(&self) -> bool {
+        self.application_cursor()
+    }
+
+    #[inline]
+    fn application_keypad(&self) -> bool {
+        self.application_keypad()
+    }
+
+    #[inline]
+    fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste()
+    }
+
+    #[inline]
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        self.mouse_protocol_mode()
+    }
+
+    #[inline]
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        self.mouse_protocol_encoding()
+    }
+
+    #[inline]
+    fn cursor_shape(&self) -> Option<CursorShape> {
+        self.cursor_shape()
+    }
+}
+
+/// Parses the output of a program into a [`Screen`].
+pub struct Parser {
+    parser: vte::Parser,
+    screen: Screen,
+}
+
+impl Parser {
+    /// Creates a parser for a screen of the given size,
+    /// keeping up to `scrollback_len` rows of history.
+    #[must_use]
+    pub fn new(rows: u16, cols: u16, scrollback_len: usize) -> Self {
+        Self {
+            parser: vte::Parser::new(),
+            screen: Screen::new(rows, cols, scrollback_len),
+        }
+    }
+
+    /// Processes the output of a program.
+    pub fn process(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.parser.advance(&mut self.screen, *byte);
+        }
+    }
+
+    /// Resizes the screen.
+    ///
+    /// Soft-wrapped rows of the main screen and its history are reflowed to the new width,
+    /// the alternate screen is cut off or padded instead.
+    pub fn set_size(&mut self, rows: u16, cols: u16) {
+        self.screen.set_size(rows, cols);
+    }
+
+    /// Returns the screen the output was parsed into.
+    #[inline]
+    #[must_use]
+    pub const fn screen(&self) -> &Screen {
+        &self.screen
+    }
+}
+
+impl std::fmt::Debug for Parser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Parser")
+            .field("screen", &self.screen)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Parser {
+    #[inline]
+    fn default() -> Self {
+        Self::new(24, 80, 0)
+    }
+}
+
+fn default_tabs(width: u16) -> Vec<bool> {
+    (0..width).map(|col| col > 0 && col % 8 == 0).collect()
+}
+
+fn designate_charset(byte: u8) -> Charset {
+    if byte == b'0' {
+        Charset::DecSpecialGraphics
+    } else {
+        Charset::Ascii
+    }
+}
+
+/// Returns the parameter at `index`, or `default` if it is missing or zero.
+fn param(params: &vte::Params, index: usize, default: u16) -> u16 {
+    match params.iter().nth(index) {
+        Some([n, ..]) if *n != 0 => *n,
+        _ => default,
+    }
+}
+
+fn color_index(n: u16) -> u8 {
+    u8::try_from(n).unwrap_or(u8::MAX)
+}
+
+/// Parses the color of SGR 38, 48 and 58, given either as subparameters
+/// or as the parameters that follow.
+fn extended_color(param: &[u16], iter: &mut vte::ParamsIter<'_>) -> Option<Color> {
+    let to_u8 = |n: u16| u8::try_from(n).ok();
+    if param.len() > 1 {
+        return match param[1..] {
+            [5, i] => Some(Color::Idx(to_u8(i)?)),
+            [2, r, g, b] | [2, _, r, g, b] => Some(Color::Rgb(to_u8(r)?, to_u8(g)?, to_u8(b)?)),
+            _ => None,
+        };
+    }
+    let mut next = || iter.next().and_then(|param| param.first().copied());
+    match next()? {
+        5 => Some(Color::Idx(to_u8(next()?)?)),
+        2 => {
+            let r = next()?;
+            let g = next()?;
+            let b = next()?;
+            Some(Color::Rgb(to_u8(r)?, to_u8(g)?, to_u8(b)?))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Modifier;
+
+    use super::*;
+
+    fn parse(rows: u16, cols: u16, stream: &[u8]) -> Parser {
+        let mut parser = Parser::new(rows, cols, 100);
+        parser.process(stream);
+        parser
+    }
+
+    fn modifier(cell: &Cell) -> Modifier {
+        let mut buf_cell = ratatui::buffer::Cell::default();
+        crate::widget::Cell::apply(cell, &mut buf_cell);
+        buf_cell.modifier
+    }
+
+    #[test]
+    fn attributes() {
+        let parser = parse(4, 20, b"\x1b[2;5;8;9ma\x1b[22;25;28;29;6;4:3mb\x1b[0;21mc");
+        let screen = parser.screen();
+        let a = screen.cell(0, 0).unwrap();
+        assert!(a.dim() && a.hidden() && a.strikethrough());
+        assert_eq!(a.blink(), Blink::Slow);
+        assert_eq!(
+            modifier(a),
+            Modifier::DIM | Modifier::SLOW_BLINK | Modifier::HIDDEN | Modifier::CROSSED_OUT
+        );
+        let b = screen.cell(0, 1).unwrap();
+        assert_eq!(b.underline(), UnderlineStyle::Curly);
+        assert_eq!(modifier(b), Modifier::UNDERLINED | Modifier::RAPID_BLINK);
+        assert_eq!(
+            screen.cell(0, 2).unwrap().underline(),
+            UnderlineStyle::Double
+        );
+    }
+
+    #[test]
+    fn colors() {
+        let parser = parse(
+            4,
+            20,
+            b"\x1b[31;102ma\x1b[38;5;208;48:2::1:2:3mb\x1b[39;49mc",
+        );
+        let screen = parser.screen();
+        let a = screen.cell(0, 0).unwrap();
+        assert_eq!((a.fgcolor(), a.bgcolor()), (Color::Idx(1), Color::Idx(10)));
+        let b = screen.cell(0, 1).unwrap();
+        assert_eq!(
+            (b.fgcolor(), b.bgcolor()),
+            (Color::Idx(208), Color::Rgb(1, 2, 3))
+        );
+        let c = screen.cell(0, 2).unwrap();
+        assert_eq!((c.fgcolor(), c.bgcolor()), (Color::Default, Color::Default));
+    }
+
+    #[test]
+    fn title() {
+        let parser = parse(4, 20, b"\x1b]0;both\x07\x1b]2;a;b\x1b\\");
+        assert_eq!(parser.screen().title(), "a;b");
+        assert_eq!(parser.screen().icon_name(), "both");
+    }
+
+    #[test]
+    fn wide_and_combining_characters() {
+        let parser = parse(4, 20, "a\u{4e2d}e\u{301}".as_bytes());
+        let screen = parser.screen();
+        assert!(screen.cell(0, 1).unwrap().is_wide());
+        assert!(screen.cell(0, 2).unwrap().is_wide_continuation());
+        assert_eq!(screen.cell(0, 3).unwrap().contents(), "e\u{301}");
+        assert_eq!(screen.cursor_position(), (0, 4));
+    }
+
+    #[test]
+    fn scrollback() {
+        let parser = parse(2, 10, b"1\r\n2\r\n3\r\n4");
+        let screen = parser.screen();
+        assert_eq!(screen.scrollback_len(), 2);
+        assert_eq!(screen.scrollback_cell(0, 0).unwrap().contents(), "2");
+        assert_eq!(screen.scrollback_cell(1, 0).unwrap().contents(), "1");
+        assert_eq!(screen.contents(), "3\n4");
+    }
+
+    #[test]
+    fn alternate_screen() {
+        let mut parser = parse(2, 10, b"main\x1b[?1049h\x1b[Halt");
+        assert!(parser.screen().alternate_screen());
+        assert_eq!(parser.screen().contents(), "alt");
+        parser.process(b"\x1b[?1049l");
+        assert_eq!(parser.screen().contents(), "main");
+        assert_eq!(parser.screen().cursor_position(), (0, 4));
+    }
+
+    #[test]
+    fn cursor_shape() {
+        let mut parser = parse(2, 10, b"\x1b[5 q");
+        assert_eq!(parser.screen().cursor_shape(), Some(CursorShape::Bar));
+        parser.process(b"\x1b[4 q");
+        assert_eq!(parser.screen().cursor_shape(), Some(CursorShape::Underline));
+        parser.process(b"\x1b[2 q");
+        assert_eq!(parser.screen().cursor_shape(), Some(CursorShape::Block));
+        parser.process(b"\x1b[ q");
+        assert_eq!(parser.screen().cursor_shape(), None);
+    }
+
+    #[test]
+    fn mouse_protocol() {
+        let mut parser = parse(2, 10, b"\x1b[?1000h\x1b[?1006h");
+        assert_eq!(
+            parser.screen().mouse_protocol_mode(),
+            MouseProtocolMode::PressRelease
+        );
+        assert_eq!(
+            parser.screen().mouse_protocol_encoding(),
+            MouseProtocolEncoding::Sgr
+        );
+        // Only the active mode is reset
+        parser.process(b"\x1b[?1003l\x1b[?1005l");
+        assert_eq!(
+            parser.screen().mouse_protocol_mode(),
+            MouseProtocolMode::PressRelease
+        );
+        assert_eq!(
+            parser.screen().mouse_protocol_encoding(),
+            MouseProtocolEncoding::Sgr
+        );
+        parser.process(b"\x1b[?1000l\x1b[?1006l");
+        assert_eq!(
+            parser.screen().mouse_protocol_mode(),
+            MouseProtocolMode::None
+        );
+        assert_eq!(
+            parser.screen().mouse_protocol_encoding(),
+            MouseProtocolEncoding::Default
+        );
+    }
+
+    #[test]
+    fn reflow() {
+        let mut parser = parse(3, 10, b"0123456789abcd\r\nnext");
+        assert!(parser.screen().row_wrapped(0));
+        parser.set_size(3, 20);
+        assert_eq!(parser.screen().contents(), "0123456789abcd\nnext");
+        assert_eq!(parser.screen().cursor_position(), (1, 4));
+        parser.set_size(3, 5);
+        assert_eq!(parser.screen().scrollback_len(), 1);
+        assert_eq!(parser.screen().contents(), "56789\nabcd\nnext");
+        assert!(parser.screen().scrollback_row_wrapped(0));
+        parser.set_size(5, 20);
+        assert_eq!(parser.screen().scrollback_len(), 0);
+        assert_eq!(parser.screen().contents(), "0123456789abcd\nnext");
+    }
+
+    fn parse_typescript(stream: &[u8]) -> Parser {
+        parse(24, 80, stream)
+    }
+
+    #[test]
+    fn sgr_modifiers() {
+        let cases: [(&[u8], Modifier); 9] = [
+            (b"\x1b[2m", Modifier::DIM),
+            (b"\x1b[5m", Modifier::SLOW_BLINK),
+            (b"\x1b[6m", Modifier::RAPID_BLINK),
+            (b"\x1b[8m", Modifier::HIDDEN),
+            (b"\x1b[9m", Modifier::CROSSED_OUT),
+            (b"\x1b[1;2m\x1b[22m", Modifier::empty()),
+            (b"\x1b[5;6m\x1b[25m", Modifier::empty()),
+            (b"\x1b[8m\x1b[28m", Modifier::empty()),
+            (b"\x1b[9m\x1b[29m", Modifier::empty()),
+        ];
+        for (sgr, modifiers) in cases {
+            let parser = parse(1, 4, &[sgr, b"a"].concat());
+            let cell = parser.screen().cell(0, 0).unwrap();
+            assert_eq!(crate::widget::Cell::modifiers(cell), modifiers);
+        }
+    }
+
+    #[test]
+    fn vttest_wrap_around() {
+        let stream = include_bytes!("../test/typescript/vttest_02_01.typescript");
+        let contents = parse_typescript(stream).screen().contents();
+        let lines: Vec<_> = contents.lines().collect();
+        assert!(lines[..3].iter().all(|line| *line == "*".repeat(80)));
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn vttest_tab_stops() {
+        let stream = include_bytes!("../test/typescript/vttest_02_02.typescript");
+        let contents = parse_typescript(stream).screen().contents();
+        let lines: Vec<_> = contents.lines().collect();
+        assert!(lines[0].contains('*'));
+        assert_eq!(lines[0], lines[1]);
+    }
+
+    #[test]
+    fn vttest_graphic_rendition() {
+        let stream = include_bytes!("../test/typescript/vttest_02_04.typescript");
+        let parser = parse_typescript(stream);
+        let contents = parser.screen().contents();
+        assert!(contents.contains("line:      ──────────"));
+        assert!(contents.contains("diamonds:  ◆◆◆◆◆◆◆◆◆◆"));
+        let stream = include_bytes!("../test/typescript/vttest_02_13.typescript");
+        let parser = parse_typescript(stream);
+        assert_eq!(parser.screen().cell(7, 0).unwrap().blink(), Blink::Slow);
+    }
+
+    /// Renders the typescript with both backends, which need to agree
+    /// unless `vt100` lacks support for the tested sequences.
+    #[cfg(feature = "vt100")]
+    #[test]
+    fn vttest_matches_vt100() {
+        use crate::testing::snapshot_screen;
+
+        let streams: [&[u8]; 11] = [
+            include_bytes!("../test/typescript/simple_ls.typescript"),
+            include_bytes!("../test/typescript/overlapping_cursor.typescript"),
+            include_bytes!("../test/typescript/vttest_02_03.typescript"),
+            include_bytes!("../test/typescript/vttest_02_05.typescript"),
+            include_bytes!("../test/typescript/vttest_02_06.typescript"),
+            include_bytes!("../test/typescript/vttest_02_07.typescript"),
+            include_bytes!("../test/typescript/vttest_02_08.typescript"),
+            include_bytes!("../test/typescript/vttest_02_09.typescript"),
+            include_bytes!("../test/typescript/vttest_02_10.typescript"),
+            include_bytes!("../test/typescript/vttest_02_12.typescript"),
+            include_bytes!("../test/typescript/vttest_02_14.typescript"),
+        ];
+        for stream in streams {
+            let mut vt100 = vt100::Parser::new(24, 80, 0);
+            vt100.process(stream);
+            let parser = parse_typescript(stream);
+            assert_eq!(
+                snapshot_screen(parser.screen()),
+                snapshot_screen(vt100.screen())
+            );
+        }
+    }
+}
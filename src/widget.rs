@@ -1,11 +1,18 @@
+use std::borrow::Cow;
+
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Alignment, Position, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Clear, Widget},
+    widgets::{Block, Clear, StatefulWidget, Widget},
 };
 
-use crate::state;
+use crate::{
+    color::{ColorDepth, Palette},
+    search::{Search, SearchMatch, SearchQuery},
+    selection::{Selection, SelectionMode},
+    state,
+};
 
 /// A trait representing a pseudo-terminal screen.
 ///
@@ -23,14 +30,184 @@ pub trait Screen {
     ///
     /// The return value is expected to be (row, column)
     fn cursor_position(&self) -> (u16, u16);
+    /// Returns the number of rows of scrollback history kept above the screen.
+    ///
+    /// Backends that cannot expose their history return `0`, which is the default.
+    fn scrollback_len(&self) -> usize {
+        0
+    }
+    /// Returns the cell at the given location in the scrollback history if it exists.
+    ///
+    /// Rows are counted upwards from the screen: row `0` is the most recent row
+    /// that scrolled off the top of the screen.
+    fn scrollback_cell(&self, _row: usize, _col: u16) -> Option<&Self::C> {
+        None
+    }
+    /// Returns whether the given row is soft-wrapped and continues on the next row.
+    fn row_wrapped(&self, _row: u16) -> bool {
+        false
+    }
+    /// Returns whether the given row of the scrollback history is soft-wrapped
+    /// and continues on the row below it.
+    ///
+    /// Rows are counted the same way as in [`Screen::scrollback_cell`].
+    fn scrollback_row_wrapped(&self, _row: usize) -> bool {
+        false
+    }
+    /// Returns the size of the screen.
+    ///
+    /// The return value is expected to be (rows, columns).
+    /// Backends that do not know their size return `(0, 0)`, which is the default.
+    fn size(&self) -> (u16, u16) {
+        (0, 0)
+    }
+    /// Returns the window title set by the application.
+    fn title(&self) -> &str {
+        ""
+    }
+    /// Returns the icon name set by the application.
+    fn icon_name(&self) -> &str {
+        ""
+    }
+    /// Returns whether the alternate screen is active.
+    fn alternate_screen(&self) -> bool {
+        false
+    }
+    /// Returns whether the application requested application cursor key mode.
+    fn application_cursor(&self) -> bool {
+        false
+    }
+    /// Returns whether the application requested application keypad mode.
+    fn application_keypad(&self) -> bool {
+        false
+    }
+    /// Returns whether the application requested bracketed paste mode.
+    fn bracketed_paste(&self) -> bool {
+        false
+    }
+    /// Returns which mouse events the application requested to be reported.
+    fn mouse_protocol_mode(&self) -> MouseProtocolMode {
+        MouseProtocolMode::None
+    }
+    /// Returns how the application requested mouse events to be encoded.
+    fn mouse_protocol_encoding(&self) -> MouseProtocolEncoding {
+        MouseProtocolEncoding::Default
+    }
+    /// Returns the cursor shape requested by the application.
+    ///
+    /// Backends that do not track the cursor shape return `None`, which is the default.
+    fn cursor_shape(&self) -> Option<CursorShape> {
+        None
+    }
+}
+
+/// The shape of the cursor, as requested with `DECSCUSR`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorShape {
+    /// A block covering the whole cell.
+    #[default]
+    Block,
+    /// A line below the cell.
+    Underline,
+    /// A vertical bar at the left of the cell.
+    Bar,
+}
+
+/// The mouse events that an application requested to be reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MouseProtocolMode {
+    /// No mouse events are reported.
+    #[default]
+    None,
+    /// Button presses are reported (mode `9`).
+    Press,
+    /// Button presses and releases are reported (mode `1000`).
+    PressRelease,
+    /// Button presses, releases and motion while a button is held are reported (mode `1002`).
+    ButtonMotion,
+    /// Button presses, releases and all motion are reported (mode `1003`).
+    AnyMotion,
+}
+
+/// The encoding that an application requested for reported mouse events.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MouseProtocolEncoding {
+    /// Coordinates are encoded as single bytes.
+    #[default]
+    Default,
+    /// Coordinates are encoded as UTF-8 characters (mode `1005`).
+    Utf8,
+    /// Events are encoded as SGR sequences (mode `1006`).
+    Sgr,
 }
 
 /// A trait for representing a single cell on a screen.
+///
+/// Implementors provide either the accessors [`Cell::symbol`], [`Cell::fg`], [`Cell::bg`] and
+/// [`Cell::modifiers`], or [`Cell::apply`]. Each side has a default that is based on the other,
+/// so implementing neither of them recurses forever.
 pub trait Cell {
     /// Whether the cell has any contents that could be rendered to the screen.
     fn has_contents(&self) -> bool;
+    /// Returns the text of the cell, a character together with its combining characters.
+    ///
+    /// Cells without contents return a space.
+    /// Defaults to the symbol that [`Cell::apply`] sets.
+    fn symbol(&self) -> Cow<'_, str> {
+        Cow::Owned(applied(self).symbol().to_owned())
+    }
+    /// Returns the foreground color of the cell.
+    ///
+    /// Defaults to the color that [`Cell::apply`] sets.
+    fn fg(&self) -> Color {
+        applied(self).fg
+    }
+    /// Returns the background color of the cell.
+    ///
+    /// Defaults to the color that [`Cell::apply`] sets.
+    fn bg(&self) -> Color {
+        applied(self).bg
+    }
+    /// Returns the text attributes of the cell.
+    ///
+    /// Besides bold, italic, underlined and reversed text, backends that track them report
+    /// dim ([`Modifier::DIM`]), crossed out ([`Modifier::CROSSED_OUT`]), blinking
+    /// ([`Modifier::SLOW_BLINK`], [`Modifier::RAPID_BLINK`]) and hidden ([`Modifier::HIDDEN`])
+    /// text.
+    /// Defaults to the attributes that [`Cell::apply`] sets.
+    fn modifiers(&self) -> Modifier {
+        applied(self).modifier
+    }
+    /// Whether the cell holds a character that is two columns wide.
+    fn is_wide(&self) -> bool {
+        false
+    }
+    /// Whether the cell is the second column of a wide character.
+    fn is_wide_continuation(&self) -> bool {
+        false
+    }
     /// Apply the contents and styling of this cell to the provided buffer cell.
-    fn apply(&self, cell: &mut ratatui::buffer::Cell);
+    ///
+    /// Defaults to the values of the accessors.
+    fn apply(&self, cell: &mut ratatui::buffer::Cell) {
+        if self.has_contents() {
+            cell.set_symbol(&self.symbol());
+        }
+        cell.set_style(Style::reset().add_modifier(self.modifiers()));
+        cell.set_fg(self.fg());
+        cell.set_bg(self.bg());
+    }
+}
+
+/// Returns a blank buffer cell with the cell applied to it,
+/// for the accessors of cells that only implement [`Cell::apply`].
+fn applied<C: Cell + ?Sized>(cell: &C) -> ratatui::buffer::Cell {
+    let mut buf_cell = ratatui::buffer::Cell::default();
+    cell.apply(&mut buf_cell);
+    buf_cell
 }
 
 /// A widget representing a pseudo-terminal screen.
@@ -68,8 +245,52 @@ pub trait Cell {
 pub struct PseudoTerminal<'a, S> {
     screen: &'a S,
     pub(crate) block: Option<Block<'a>>,
-    style: Option<Style>,
+    pub(crate) style: Option<Style>,
     pub(crate) cursor: Cursor,
+    pub(crate) scrollback: usize,
+    pub(crate) selection_style: Style,
+    pub(crate) search_style: Style,
+    pub(crate) current_search_style: Style,
+    pub(crate) palette: Option<Palette>,
+    pub(crate) color_depth: ColorDepth,
+    pub(crate) viewport: Viewport,
+    pub(crate) alignment: Alignment,
+    pub(crate) vertical_alignment: VerticalAlignment,
+    pub(crate) fill_symbol: String,
+    pub(crate) fill_style: Style,
+}
+
+/// The vertical alignment of the screen when the area of the widget is taller than the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlignment {
+    /// Aligns the screen with the top of the area.
+    #[default]
+    Top,
+    /// Centers the screen in the area.
+    Center,
+    /// Aligns the screen with the bottom of the area.
+    Bottom,
+}
+
+/// Which part of the screen is shown when the area of the widget is smaller than the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Viewport {
+    /// Shows the top left corner of the screen.
+    #[default]
+    TopLeft,
+    /// Shows the bottom rows of the screen, where the prompt usually is.
+    Bottom,
+    /// Scrolls just as far as needed to keep the cursor visible.
+    FollowCursor,
+    /// Shows the screen starting at the given row and column.
+    ///
+    /// The offset is clamped so that the area does not extend past the screen.
+    Offset {
+        /// The row of the screen shown in the first row of the area.
+        row: u16,
+        /// The column of the screen shown in the first column of the area.
+        col: u16,
+    },
 }
 
 #[non_exhaustive]
@@ -78,6 +299,8 @@ pub struct Cursor {
     pub(crate) symbol: String,
     pub(crate) style: Style,
     pub(crate) overlay_style: Style,
+    pub(crate) shape: Option<CursorShape>,
+    pub(crate) hardware: bool,
 }
 
 impl Cursor {
@@ -140,162 +363,822 @@ impl Cursor {
     /// let cursor = Cursor::default().overlay_style(Style::default());
     /// ```
     #[inline]
-    #[must_use]
-    pub const fn overlay_style(mut self, overlay_style: Style) -> Self {
-        self.overlay_style = overlay_style;
-        self
+    #[must_use]
+    pub const fn overlay_style(mut self, overlay_style: Style) -> Self {
+        self.overlay_style = overlay_style;
+        self
+    }
+
+    /// Forces the shape of the cursor.
+    ///
+    /// By default the cursor follows the shape requested by the program,
+    /// if the [`Screen`] reports it, and is drawn as a block otherwise.
+    ///
+    /// On an empty cell, a block is drawn with the [`Cursor::symbol`], an underline with `▁`
+    /// and a bar with `▏`. On a cell with contents, a block is drawn with the
+    /// [`Cursor::overlay_style`] and an underline underlines the contents in the colors of the
+    /// overlay style. A bar is drawn as `▕` in the empty cell to the left of the cursor,
+    /// and like an underline when there is none, since it cannot be drawn next to a character
+    /// inside a cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::widget::{Cursor, CursorShape};
+    ///
+    /// let cursor = Cursor::default().shape(CursorShape::Bar);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn shape(mut self, shape: CursorShape) -> Self {
+        self.shape = Some(shape);
+        self
+    }
+
+    /// Uses the cursor of the host terminal instead of drawing one (default = drawn).
+    ///
+    /// The widget does not draw the cursor, but reports its position through
+    /// [`PseudoTerminalState::cursor_position`], which is meant to be handed to
+    /// [`Frame::set_cursor_position`](ratatui::Frame::set_cursor_position).
+    /// This shows the real cursor shape of the host terminal and lets input methods
+    /// place their popups at the cursor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::{backend::TestBackend, Terminal};
+    /// use tui_term::widget::{Cursor, PseudoTerminal, PseudoTerminalState};
+    ///
+    /// let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    /// let parser = vt100::Parser::new(24, 80, 0);
+    /// let mut state = PseudoTerminalState::default();
+    /// terminal
+    ///     .draw(|f| {
+    ///         let cursor = Cursor::default().hardware(true);
+    ///         let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
+    ///         f.render_stateful_widget(pseudo_term, f.area(), &mut state);
+    ///         if let Some(position) = state.cursor_position() {
+    ///             f.set_cursor_position(position);
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn hardware(mut self, hardware: bool) -> Self {
+        self.hardware = hardware;
+        self
+    }
+
+    /// Set the visibility of the cursor (default = shown)
+    #[inline]
+    #[must_use]
+    pub const fn visibility(mut self, show: bool) -> Self {
+        self.show = show;
+        self
+    }
+
+    /// Show the cursor (default)
+    #[inline]
+    pub fn show(&mut self) {
+        self.show = true;
+    }
+
+    /// Hide the cursor
+    #[inline]
+    pub fn hide(&mut self) {
+        self.show = false;
+    }
+}
+
+impl Default for Cursor {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            show: true,
+            symbol: "\u{2588}".into(), //"█".
+            style: Style::default().fg(Color::Gray),
+            overlay_style: Style::default().add_modifier(Modifier::REVERSED),
+            shape: None,
+            hardware: false,
+        }
+    }
+}
+
+impl<'a, S: Screen> PseudoTerminal<'a, S> {
+    /// Creates a new instance of `PseudoTerminal`.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen`: The reference to the `Screen`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::widget::PseudoTerminal;
+    /// use vt100::Parser;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(screen: &'a S) -> Self {
+        PseudoTerminal {
+            screen,
+            block: None,
+            style: None,
+            cursor: Cursor::default(),
+            scrollback: 0,
+            selection_style: Style::default().add_modifier(Modifier::REVERSED),
+            search_style: Style::default().fg(Color::Black).bg(Color::Yellow),
+            current_search_style: Style::default().fg(Color::Black).bg(Color::LightRed),
+            palette: None,
+            color_depth: ColorDepth::TrueColor,
+            viewport: Viewport::TopLeft,
+            alignment: Alignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            fill_symbol: " ".into(),
+            fill_style: Style::default(),
+        }
+    }
+
+    /// Sets the block for the `PseudoTerminal`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block`: The `Block` to set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::widgets::Block;
+    /// use tui_term::widget::PseudoTerminal;
+    /// use vt100::Parser;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let block = Block::default();
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).block(block);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets the cursor configuration for the `PseudoTerminal`.
+    ///
+    /// The `cursor` method allows configuring the appearance of the cursor within the
+    /// `PseudoTerminal` widget.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor`: The `Cursor` configuration to set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ratatui::style::Style;
+    /// use tui_term::widget::{Cursor, PseudoTerminal};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let cursor = Cursor::default().symbol("|").style(Style::default());
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cursor(mut self, cursor: Cursor) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    /// Sets the style for `PseudoTerminal`.
+    ///
+    /// The style fills the whole area of the widget and is the base style of the screen:
+    /// its foreground and background colors are used for cells with the default colors,
+    /// while colors set by the program are kept, and its modifiers are added to every cell.
+    /// The colors of the style take precedence over the default colors of a
+    /// [`Palette`].
+    ///
+    /// # Arguments
+    ///
+    /// * `style`: The `Style` to set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::Style;
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let style = Style::default();
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).style(style);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Sets the number of rows the view is scrolled up into the scrollback history.
+    ///
+    /// An offset of `0` shows the live screen. The offset is clamped to
+    /// [`Screen::scrollback_len`] when rendering.
+    /// When rendered as a [`StatefulWidget`], the offset of the
+    /// [`PseudoTerminalState`] is used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `scrollback`: The number of rows to scroll up.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::{widget::PseudoTerminal, ScrollbackScreen};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 1000);
+    /// let screen = ScrollbackScreen::new(&mut parser, 10, 24);
+    /// let pseudo_term = PseudoTerminal::new(&screen).scrollback(10);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn scrollback(mut self, scrollback: usize) -> Self {
+        self.scrollback = scrollback;
+        self
+    }
+
+    /// Sets the style used to highlight the [`Selection`] of the [`PseudoTerminalState`].
+    ///
+    /// Defaults to reversing the colors of selected cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `style`: The `Style` to patch selected cells with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let style = Style::default().bg(Color::Blue);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).selection_style(style);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn selection_style(mut self, style: Style) -> Self {
+        self.selection_style = style;
+        self
+    }
+
+    /// Sets the style used to highlight the matches of the [`Search`] of the
+    /// [`PseudoTerminalState`].
+    ///
+    /// # Arguments
+    ///
+    /// * `style`: The `Style` to patch matching cells with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let style = Style::default().bg(Color::Green);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).search_style(style);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn search_style(mut self, style: Style) -> Self {
+        self.search_style = style;
+        self
+    }
+
+    /// Sets the style used to highlight the current match of the [`Search`] of the
+    /// [`PseudoTerminalState`].
+    ///
+    /// # Arguments
+    ///
+    /// * `style`: The `Style` to patch the cells of the current match with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let style = Style::default().bg(Color::Magenta);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).current_search_style(style);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn current_search_style(mut self, style: Style) -> Self {
+        self.current_search_style = style;
+        self
+    }
+
+    /// Sets the [`Palette`] that the colors of the screen are mapped to.
+    ///
+    /// Without a palette, indexed and default colors are rendered with the palette of the
+    /// host terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The `Palette` to map colors with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::{color::Palette, widget::PseudoTerminal};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).palette(Palette::gruvbox_dark());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Sets the [`ColorDepth`] of the host terminal.
+    ///
+    /// All colors rendered by the widget, including the highlights and the cursor, are
+    /// reduced to the closest color the host terminal is able to display.
+    ///
+    /// # Arguments
+    ///
+    /// * `color_depth`: The `ColorDepth` to reduce colors to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::{color::ColorDepth, widget::PseudoTerminal};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).color_depth(ColorDepth::Ansi16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.color_depth = color_depth;
+        self
+    }
+
+    /// Sets which part of the screen is shown when the area is smaller than the screen.
+    ///
+    /// The offset is computed from [`Screen::size`], so backends that do not report
+    /// their size always show the top left corner.
+    ///
+    /// # Arguments
+    ///
+    /// * `viewport`: The `Viewport` to show.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::widget::{PseudoTerminal, Viewport};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).viewport(Viewport::Bottom);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Sets the horizontal alignment of the screen when the area is wider than the screen.
+    ///
+    /// The alignment is computed from [`Screen::size`], so the screen of backends that do not
+    /// report their size is always aligned to the left.
+    ///
+    /// # Arguments
+    ///
+    /// * `alignment`: The `Alignment` of the screen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::layout::Alignment;
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).alignment(Alignment::Center);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the vertical alignment of the screen when the area is taller than the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertical_alignment`: The `VerticalAlignment` of the screen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::widget::{PseudoTerminal, VerticalAlignment};
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term =
+    ///     PseudoTerminal::new(parser.screen()).vertical_alignment(VerticalAlignment::Center);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn vertical_alignment(mut self, vertical_alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = vertical_alignment;
+        self
+    }
+
+    /// Sets the symbol that fills the part of the area that is not covered by the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol`: The symbol to fill the unused area with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term = PseudoTerminal::new(parser.screen()).fill_symbol("░");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn fill_symbol(mut self, symbol: &str) -> Self {
+        self.fill_symbol = symbol.into();
+        self
+    }
+
+    /// Sets the style of the part of the area that is not covered by the screen.
+    ///
+    /// The fill style is applied on top of the [`PseudoTerminal::style`].
+    ///
+    /// # Arguments
+    ///
+    /// * `style`: The `Style` of the unused area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_term::widget::PseudoTerminal;
+    ///
+    /// let mut parser = vt100::Parser::new(24, 80, 0);
+    /// let pseudo_term =
+    ///     PseudoTerminal::new(parser.screen()).fill_style(Style::default().fg(Color::DarkGray));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn fill_style(mut self, style: Style) -> Self {
+        self.fill_style = style;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn screen(&self) -> &S {
+        self.screen
+    }
+}
+
+impl<S: Screen> Widget for PseudoTerminal<'_, S> {
+    #[inline]
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = PseudoTerminalState::default().with_scrollback(self.scrollback);
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
+
+impl<S: Screen> StatefulWidget for PseudoTerminal<'_, S> {
+    type State = PseudoTerminalState;
+
+    #[inline]
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+        if let Some(style) = self.style {
+            buf.set_style(area, style);
+        }
+        let area = self.block.as_ref().map_or(area, |b| {
+            let inner_area = b.inner(area);
+            b.clone().render(area, buf);
+            inner_area
+        });
+        state::handle(&self, area, buf, state);
+    }
+}
+
+/// The state of a [`PseudoTerminal`] that is kept between frames.
+///
+/// Rendering the `PseudoTerminal` as a [`StatefulWidget`] allows interactive features,
+/// like scrolling through the history, to persist while the widget itself is rebuilt
+/// every frame.
+///
+/// # Example
+///
+/// ```rust
+/// use ratatui::{backend::TestBackend, Terminal};
+/// use tui_term::widget::{PseudoTerminal, PseudoTerminalState};
+///
+/// let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+/// let parser = vt100::Parser::new(24, 80, 1000);
+/// let mut state = PseudoTerminalState::default();
+/// state.scroll_up(5);
+/// terminal
+///     .draw(|f| {
+///         let pseudo_term = PseudoTerminal::new(parser.screen());
+///         f.render_stateful_widget(pseudo_term, f.area(), &mut state);
+///     })
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct PseudoTerminalState {
+    pub(crate) scrollback: usize,
+    pub(crate) area: Rect,
+    pub(crate) selection: Option<Selection>,
+    pub(crate) search: Option<Search>,
+    pub(crate) cursor_position: Option<Position>,
+    pub(crate) viewport_offset: (u16, u16),
+    pub(crate) top_line: usize,
+    pub(crate) reveal_line: Option<usize>,
+}
+
+impl PseudoTerminalState {
+    /// Creates a new `PseudoTerminalState` showing the live screen.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of rows the view is scrolled up into the scrollback history.
+    #[inline]
+    #[must_use]
+    pub const fn with_scrollback(mut self, scrollback: usize) -> Self {
+        self.scrollback = scrollback;
+        self.reveal_line = None;
+        self
+    }
+
+    /// Returns the number of rows the view is scrolled up into the scrollback history.
+    ///
+    /// The offset is clamped to [`Screen::scrollback_len`] every time the widget is rendered.
+    #[inline]
+    #[must_use]
+    pub const fn scrollback(&self) -> usize {
+        self.scrollback
+    }
+
+    /// Sets the number of rows the view is scrolled up into the scrollback history.
+    #[inline]
+    pub fn set_scrollback(&mut self, scrollback: usize) {
+        self.scrollback = scrollback;
+        self.reveal_line = None;
+    }
+
+    /// Scrolls the view up into the scrollback history by the given amount of rows.
+    #[inline]
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.scrollback = self.scrollback.saturating_add(rows);
+        self.reveal_line = None;
+    }
+
+    /// Scrolls the view down towards the live screen by the given amount of rows.
+    #[inline]
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.scrollback = self.scrollback.saturating_sub(rows);
+        self.reveal_line = None;
+    }
+
+    /// Scrolls the view back to the live screen.
+    #[inline]
+    pub fn scroll_to_bottom(&mut self) {
+        self.scrollback = 0;
+        self.reveal_line = None;
+    }
+
+    /// Returns the area the screen was rendered to in the last frame,
+    /// excluding the block.
+    #[inline]
+    #[must_use]
+    pub const fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Returns the (row, column) of the screen that was shown in the top left corner of the
+    /// area in the last frame, see [`PseudoTerminal::viewport`] and [`Self::next_match`].
+    #[inline]
+    #[must_use]
+    pub const fn viewport_offset(&self) -> (u16, u16) {
+        self.viewport_offset
+    }
+
+    /// Returns the position of the cursor in the buffer, as rendered in the last frame.
+    ///
+    /// Returns `None` if the cursor was hidden or outside of the rendered area.
+    /// The position is reported whether or not the cursor is drawn by the widget,
+    /// see [`Cursor::hardware`].
+    #[inline]
+    #[must_use]
+    pub const fn cursor_position(&self) -> Option<Position> {
+        self.cursor_position
+    }
+
+    /// Translates a position of the rendered buffer, like the column and row of a mouse
+    /// event, into the (row, column) of the screen cell rendered at that position.
+    ///
+    /// Returns `None` if the position is outside of the area rendered in the last frame.
+    #[inline]
+    #[must_use]
+    pub fn screen_position(&self, position: Position) -> Option<(u16, u16)> {
+        let (row_offset, col_offset) = self.viewport_offset;
+        self.area.contains(position).then(|| {
+            (
+                position.y - self.area.y + row_offset,
+                position.x - self.area.x + col_offset,
+            )
+        })
+    }
+
+    /// Starts a new selection at the given (row, column) of the screen,
+    /// as rendered in the last frame.
+    ///
+    /// The selection is anchored to the lines of the history, see [`Selection`],
+    /// so it stays on the same text when the view is scrolled or new output arrives.
+    ///
+    /// # Example
+    ///
+    /// Selecting text by dragging the mouse:
+    ///
+    /// ```
+    /// use ratatui::layout::Position;
+    /// use tui_term::{selection::SelectionMode, widget::PseudoTerminalState};
+    ///
+    /// let mut state = PseudoTerminalState::default();
+    /// # let (down, drag) = (Position::new(0, 0), Position::new(0, 0));
+    /// // on mouse down
+    /// if let Some(position) = state.screen_position(down) {
+    ///     state.start_selection(position, SelectionMode::Linear);
+    /// }
+    /// // on mouse drag
+    /// if let Some(position) = state.screen_position(drag) {
+    ///     state.extend_selection(position);
+    /// }
+    /// ```
+    #[inline]
+    pub fn start_selection(&mut self, position: (u16, u16), mode: SelectionMode) {
+        self.selection = Some(Selection::new(self.view_line(position), mode));
+    }
+
+    /// Moves the end of the current selection to the given (row, column) of the screen,
+    /// as rendered in the last frame.
+    ///
+    /// Does nothing if there is no selection.
+    #[inline]
+    pub fn extend_selection(&mut self, position: (u16, u16)) {
+        let position = self.view_line(position);
+        if let Some(selection) = &mut self.selection {
+            selection.extend(position);
+        }
+    }
+
+    /// Selects the word at the given (row, column) of the screen,
+    /// as rendered in the last frame.
+    #[inline]
+    pub fn select_word<S: Screen>(&mut self, screen: &S, position: (u16, u16)) {
+        self.selection = Some(Selection::word(screen, self.view_line(position)));
     }
 
-    /// Set the visibility of the cursor (default = shown)
+    /// Selects the line at the given row of the screen, as rendered in the last frame,
+    /// including the rows it is soft-wrapped across.
     #[inline]
-    #[must_use]
-    pub const fn visibility(mut self, show: bool) -> Self {
-        self.show = show;
-        self
+    pub fn select_line<S: Screen>(&mut self, screen: &S, row: u16) {
+        self.selection = Some(Selection::line(screen, self.view_line((row, 0)).0));
     }
 
-    /// Show the cursor (default)
+    /// Translates a (row, column) of the view rendered in the last frame into the
+    /// (line, column) of the history.
+    fn view_line(&self, (row, col): (u16, u16)) -> (usize, u16) {
+        (self.top_line + usize::from(row), col)
+    }
+
+    /// Sets the current selection.
     #[inline]
-    pub fn show(&mut self) {
-        self.show = true;
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
     }
 
-    /// Hide the cursor
+    /// Clears the current selection.
     #[inline]
-    pub fn hide(&mut self) {
-        self.show = false;
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
     }
-}
 
-impl Default for Cursor {
+    /// Returns the current selection.
     #[inline]
-    fn default() -> Self {
-        Self {
-            show: true,
-            symbol: "\u{2588}".into(), //"█".
-            style: Style::default().fg(Color::Gray),
-            overlay_style: Style::default().add_modifier(Modifier::REVERSED),
-        }
+    #[must_use]
+    pub const fn selection(&self) -> Option<&Selection> {
+        self.selection.as_ref()
     }
-}
 
-impl<'a, S: Screen> PseudoTerminal<'a, S> {
-    /// Creates a new instance of `PseudoTerminal`.
-    ///
-    /// # Arguments
+    /// Returns the text of the current selection on the given screen.
     ///
-    /// * `screen`: The reference to the `Screen`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use tui_term::widget::PseudoTerminal;
-    /// use vt100::Parser;
-    ///
-    /// let mut parser = vt100::Parser::new(24, 80, 0);
-    /// let pseudo_term = PseudoTerminal::new(parser.screen());
-    /// ```
+    /// The screen should be the one the [`PseudoTerminal`] was last rendered with.
     #[inline]
     #[must_use]
-    pub fn new(screen: &'a S) -> Self {
-        PseudoTerminal {
-            screen,
-            block: None,
-            style: None,
-            cursor: Cursor::default(),
-        }
+    pub fn selected_text<S: Screen>(&self, screen: &S) -> Option<String> {
+        self.selection
+            .as_ref()
+            .map(|selection| selection.text(screen))
     }
 
-    /// Sets the block for the `PseudoTerminal`.
-    ///
-    /// # Arguments
+    /// Searches the screen and its scrollback history for the query.
     ///
-    /// * `block`: The `Block` to set.
+    /// The matches are highlighted until the search is cleared.
+    /// Run the search again to pick up output processed after it was started.
     ///
     /// # Example
     ///
     /// ```
-    /// use ratatui::widgets::Block;
-    /// use tui_term::widget::PseudoTerminal;
-    /// use vt100::Parser;
+    /// use tui_term::{search::SearchQuery, widget::PseudoTerminalState};
     ///
     /// let mut parser = vt100::Parser::new(24, 80, 0);
-    /// let block = Block::default();
-    /// let pseudo_term = PseudoTerminal::new(parser.screen()).block(block);
+    /// parser.process(b"error: first\r\nerror: second");
+    /// let mut state = PseudoTerminalState::default();
+    /// state.start_search(parser.screen(), SearchQuery::literal("error"));
+    /// // Jump to the most recent match
+    /// let current = state.previous_match(parser.screen()).unwrap();
+    /// assert_eq!(current.start(), (1, 0));
     /// ```
     #[inline]
-    #[must_use]
-    pub fn block(mut self, block: Block<'a>) -> Self {
-        self.block = Some(block);
-        self
+    pub fn start_search<S: Screen>(&mut self, screen: &S, query: SearchQuery) {
+        self.search = Some(Search::new(screen, query));
+        self.reveal_line = None;
     }
 
-    /// Sets the cursor configuration for the `PseudoTerminal`.
-    ///
-    /// The `cursor` method allows configuring the appearance of the cursor within the
-    /// `PseudoTerminal` widget.
-    ///
-    /// # Arguments
-    ///
-    /// * `cursor`: The `Cursor` configuration to set.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use ratatui::style::Style;
-    /// use tui_term::widget::{Cursor, PseudoTerminal};
-    ///
-    /// let mut parser = vt100::Parser::new(24, 80, 0);
-    /// let cursor = Cursor::default().symbol("|").style(Style::default());
-    /// let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
-    /// ```
+    /// Clears the current search.
     #[inline]
-    #[must_use]
-    pub fn cursor(mut self, cursor: Cursor) -> Self {
-        self.cursor = cursor;
-        self
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.reveal_line = None;
     }
 
-    /// Sets the style for `PseudoTerminal`.
-    ///
-    /// # Arguments
-    ///
-    /// * `style`: The `Style` to set.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use ratatui::style::Style;
-    /// use tui_term::widget::PseudoTerminal;
-    ///
-    /// let mut parser = vt100::Parser::new(24, 80, 0);
-    /// let style = Style::default();
-    /// let pseudo_term = PseudoTerminal::new(parser.screen()).style(style);
-    /// ```
+    /// Returns the current search.
     #[inline]
     #[must_use]
-    pub const fn style(mut self, style: Style) -> Self {
-        self.style = Some(style);
-        self
+    pub const fn search(&self) -> Option<&Search> {
+        self.search.as_ref()
     }
 
+    /// Returns the current search mutably.
     #[inline]
     #[must_use]
-    pub const fn screen(&self) -> &S {
-        self.screen
+    pub fn search_mut(&mut self) -> Option<&mut Search> {
+        self.search.as_mut()
     }
-}
 
-impl<S: Screen> Widget for PseudoTerminal<'_, S> {
-    #[inline]
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        Clear.render(area, buf);
-        let area = self.block.as_ref().map_or(area, |b| {
-            let inner_area = b.inner(area);
-            b.clone().render(area, buf);
-            inner_area
-        });
-        state::handle(&self, area, buf);
+    /// Moves to the next match of the current search,
+    /// scrolling the view to it if it is not visible.
+    ///
+    /// The match is kept in the area until the view is scrolled, even if that means showing
+    /// other rows of the screen than the [`Viewport`] would.
+    pub fn next_match<S: Screen>(&mut self, screen: &S) -> Option<SearchMatch> {
+        let found = *self.search.as_mut()?.next_match()?;
+        self.scroll_to_line(screen, found.start().0);
+        Some(found)
+    }
+
+    /// Moves to the previous match of the current search,
+    /// scrolling the view to it if it is not visible.
+    ///
+    /// The match is kept in the area until the view is scrolled, see [`Self::next_match`].
+    pub fn previous_match<S: Screen>(&mut self, screen: &S) -> Option<SearchMatch> {
+        let found = *self.search.as_mut()?.previous_match()?;
+        self.scroll_to_line(screen, found.start().0);
+        Some(found)
+    }
+
+    /// Scrolls the view so that the line, counted from the top of the history, is visible.
+    ///
+    /// The line is recorded, so that it is kept visible when the widget is rendered.
+    fn scroll_to_line<S: Screen>(&mut self, screen: &S, line: usize) {
+        let scrollback = self.scrollback.min(screen.scrollback_len());
+        let (row_offset, rows) = (self.viewport_offset.0, self.area.height);
+        (self.scrollback, _) = state::reveal_line(screen, line, scrollback, row_offset, rows);
+        self.reveal_line = Some(line);
     }
 }
 
@@ -304,19 +1187,25 @@ mod tests {
     use ratatui::{backend::TestBackend, widgets::Borders, Terminal};
 
     use super::*;
+    use crate::{
+        testing::{snapshot_frame, snapshot_screen, snapshot_stateful, snapshot_widget},
+        ScrollbackScreen,
+    };
 
     fn snapshot_typescript(stream: &[u8]) -> String {
-        let backend = TestBackend::new(80, 24);
-        let mut terminal = Terminal::new(backend).unwrap();
         let mut parser = vt100::Parser::new(24, 80, 0);
         parser.process(stream);
-        let pseudo_term = PseudoTerminal::new(parser.screen());
-        terminal
-            .draw(|f| {
-                f.render_widget(pseudo_term, f.area());
-            })
-            .unwrap();
-        format!("{:?}", terminal.backend().buffer())
+        snapshot_screen(parser.screen())
+    }
+
+    fn snapshot_scrollback(scrollback: usize) -> String {
+        let mut parser = vt100::Parser::new(5, 20, 100);
+        parser.process(b"line 1");
+        for line in 2..=10 {
+            parser.process(format!("\r\nline {line}").as_bytes());
+        }
+        let screen = ScrollbackScreen::new(&mut parser, scrollback, 5);
+        snapshot_widget(PseudoTerminal::new(&screen).scrollback(scrollback), 20, 5)
     }
 
     #[test]
@@ -435,6 +1324,54 @@ mod tests {
         insta::assert_snapshot!(view);
     }
     #[test]
+    fn cursor_shapes() {
+        // The cursor is placed on a cell with contents after a cell with contents,
+        // on a cell with contents after an empty cell and on an empty cell
+        let mut on_contents = vt100::Parser::new(1, 10, 0);
+        on_contents.process(b"ab\x1b[1;2H");
+        let mut after_empty = vt100::Parser::new(1, 10, 0);
+        after_empty.process(b"a b\x1b[1;3H");
+        let mut on_empty = vt100::Parser::new(1, 10, 0);
+        on_empty.process(b"ab");
+        let parsers = [&on_contents, &after_empty, &on_empty];
+        let shapes = [CursorShape::Block, CursorShape::Underline, CursorShape::Bar];
+        let view = snapshot_frame(10, 9, |f| {
+            for (row, shape) in (0..).zip(shapes) {
+                for (offset, parser) in (0..).zip(parsers) {
+                    let cursor = Cursor::default().shape(shape);
+                    let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
+                    f.render_widget(pseudo_term, Rect::new(0, row * 3 + offset, 10, 1));
+                }
+            }
+        });
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn underline_cursor_overlay_style() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 1));
+        let mut parser = vt100::Parser::new(1, 10, 0);
+        parser.process(b"ab\x1b[1;2H");
+        let overlay_style = Style::default()
+            .fg(Color::LightRed)
+            .add_modifier(Modifier::REVERSED);
+        let cursor = Cursor::default()
+            .shape(CursorShape::Underline)
+            .overlay_style(overlay_style);
+        let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
+        Widget::render(pseudo_term, buf.area, &mut buf);
+        assert_eq!(buf[(1, 0)].fg, Color::LightRed);
+        assert_eq!(buf[(1, 0)].modifier, Modifier::UNDERLINED);
+    }
+    #[cfg(feature = "vte")]
+    #[test]
+    fn cursor_shape_from_screen() {
+        let mut parser = crate::vte_backend::Parser::new(1, 10, 0);
+        parser.process(b"ab\x1b[3 q");
+        let pseudo_term = PseudoTerminal::new(parser.screen());
+        let view = snapshot_widget(pseudo_term, 10, 1);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
     fn overlapping_cursor_alternate_style() {
         let stream = include_bytes!("../test/typescript/overlapping_cursor.typescript");
         let backend = TestBackend::new(80, 24);
@@ -490,6 +1427,31 @@ mod tests {
         insta::assert_snapshot!(view);
     }
     #[test]
+    fn base_style() {
+        let stream = b"default \x1b[31mred\x1b[0m \x1b[44mblue\x1b[0m \x1b[3mitalic\x1b[0m";
+        // The screen is smaller than the area, the rest is filled with the style
+        let mut parser = vt100::Parser::new(2, 30, 0);
+        parser.process(stream);
+        let style = Style::default()
+            .fg(Color::White)
+            .bg(Color::Black)
+            .add_modifier(Modifier::BOLD);
+        let pseudo_term = PseudoTerminal::new(parser.screen()).style(style);
+        let view = snapshot_widget(pseudo_term, 40, 4);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn base_style_with_palette() {
+        let stream = b"default \x1b[31mred\x1b[0m \x1b[44mblue\x1b[0m";
+        let mut parser = vt100::Parser::new(2, 30, 0);
+        parser.process(stream);
+        let pseudo_term = PseudoTerminal::new(parser.screen())
+            .style(Style::default().fg(Color::Rgb(1, 2, 3)))
+            .palette(Palette::solarized_dark());
+        let view = snapshot_widget(pseudo_term, 30, 2);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
     fn italic_text() {
         let stream = b"[3mThis line will be displayed in italic.[0m This should have no style.";
         let view = snapshot_typescript(stream);
@@ -522,6 +1484,36 @@ mod tests {
         insta::assert_snapshot!(view);
     }
 
+    #[test]
+    fn wide_cjk() {
+        let stream = include_bytes!("../test/typescript/wide_cjk.typescript");
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn wide_emoji() {
+        let stream = include_bytes!("../test/typescript/wide_emoji.typescript");
+        let view = snapshot_typescript(stream);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn wide_char_clipped() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process("ab中文\r\na中文".as_bytes());
+        let pseudo_term = PseudoTerminal::new(parser.screen());
+        let view = snapshot_widget(pseudo_term, 5, 2);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn wide_char_cursor() {
+        let mut parser = vt100::Parser::new(2, 10, 0);
+        parser.process("a中文\x1b[1;3H".as_bytes());
+        let style = Style::default().bg(Color::Cyan);
+        let cursor = Cursor::default().overlay_style(style);
+        let pseudo_term = PseudoTerminal::new(parser.screen()).cursor(cursor);
+        let view = snapshot_widget(pseudo_term, 10, 2);
+        insta::assert_snapshot!(view);
+    }
     #[test]
     fn vttest_02_01() {
         let stream = include_bytes!("../test/typescript/vttest_02_01.typescript");
@@ -612,4 +1604,374 @@ mod tests {
         let view = snapshot_typescript(stream);
         insta::assert_snapshot!(view);
     }
+    #[test]
+    fn scrollback_offset() {
+        let view = snapshot_scrollback(3);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn scrollback_offset_clamped() {
+        let view = snapshot_scrollback(usize::MAX);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn hardware_cursor() {
+        let backend = TestBackend::new(12, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut parser = vt100::Parser::new(2, 10, 0);
+        parser.process(b"ab\r\ncd");
+        let mut state = PseudoTerminalState::default();
+        terminal
+            .draw(|f| {
+                let cursor = Cursor::default().hardware(true);
+                let pseudo_term = PseudoTerminal::new(parser.screen())
+                    .block(Block::default().borders(Borders::ALL))
+                    .cursor(cursor);
+                f.render_stateful_widget(pseudo_term, f.area(), &mut state);
+                if let Some(position) = state.cursor_position() {
+                    f.set_cursor_position(position);
+                }
+            })
+            .unwrap();
+        assert_eq!(state.cursor_position(), Some(Position::new(3, 2)));
+        assert_eq!(terminal.get_cursor_position().unwrap(), Position::new(3, 2));
+        let view = format!("{:?}", terminal.backend().buffer());
+        insta::assert_snapshot!(view);
+
+        parser.process(b"\x1b[?25l");
+        terminal
+            .draw(|f| {
+                let pseudo_term = PseudoTerminal::new(parser.screen());
+                f.render_stateful_widget(pseudo_term, f.area(), &mut state);
+            })
+            .unwrap();
+        assert_eq!(state.cursor_position(), None);
+    }
+    #[test]
+    fn viewports() {
+        let mut parser = vt100::Parser::new(6, 10, 0);
+        parser.process(b"0abcdefghi\r\n1abcdefghi\r\n2abcdefghi\r\n");
+        parser.process(b"3abcdefghi\r\n4abcdefghi\r\n5abcdefghi\x1b[4;8H");
+        let viewports = [
+            Viewport::TopLeft,
+            Viewport::Bottom,
+            Viewport::FollowCursor,
+            Viewport::Offset { row: 1, col: 100 },
+        ];
+        let view = snapshot_frame(23, 3, |f| {
+            for (index, viewport) in (0..).zip(viewports) {
+                let pseudo_term = PseudoTerminal::new(parser.screen()).viewport(viewport);
+                f.render_widget(pseudo_term, Rect::new(index * 6, 0, 5, 3));
+            }
+        });
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn viewport_screen_position() {
+        let mut parser = vt100::Parser::new(6, 10, 0);
+        parser.process(b"0abcdefghi\r\n1abcdefghi\r\n2abcdefghi\r\n");
+        parser.process(b"3abcdefghi\r\n4abcdefghi\r\n5abcdefghi");
+        let mut state = PseudoTerminalState::default();
+        let pseudo_term = PseudoTerminal::new(parser.screen()).viewport(Viewport::Bottom);
+        snapshot_stateful(pseudo_term, &mut state, 5, 3);
+        assert_eq!(state.viewport_offset(), (3, 0));
+        assert_eq!(state.screen_position(Position::new(1, 2)), Some((5, 1)));
+        state.start_selection((5, 0), SelectionMode::Linear);
+        state.extend_selection((5, 4));
+        assert_eq!(
+            state.selected_text(parser.screen()).as_deref(),
+            Some("5abcd")
+        );
+    }
+    #[test]
+    fn alignments() {
+        let mut parser = vt100::Parser::new(2, 4, 0);
+        parser.process(b"abcd\r\nefgh");
+        let alignments = [
+            (Alignment::Left, VerticalAlignment::Top),
+            (Alignment::Center, VerticalAlignment::Center),
+            (Alignment::Right, VerticalAlignment::Bottom),
+        ];
+        let view = snapshot_frame(20, 10, |f| {
+            for (index, (alignment, vertical_alignment)) in (0..).zip(alignments) {
+                let pseudo_term = PseudoTerminal::new(parser.screen())
+                    .cursor(Cursor::default().visibility(false))
+                    .alignment(alignment)
+                    .vertical_alignment(vertical_alignment)
+                    .fill_symbol("·");
+                f.render_widget(pseudo_term, Rect::new(index * 7, 0, 6, 10));
+            }
+        });
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn alignment_without_size() {
+        // A screen that does not report its size, which is then counted from its cells
+        struct SizelessScreen<'a>(&'a vt100::Screen);
+        impl Screen for SizelessScreen<'_> {
+            type C = vt100::Cell;
+
+            fn cell(&self, row: u16, col: u16) -> Option<&Self::C> {
+                Screen::cell(self.0, row, col)
+            }
+
+            fn hide_cursor(&self) -> bool {
+                true
+            }
+
+            fn cursor_position(&self) -> (u16, u16) {
+                Screen::cursor_position(self.0)
+            }
+        }
+        let mut parser = vt100::Parser::new(2, 4, 0);
+        parser.process(b"abcd\r\nefgh");
+        let screen = SizelessScreen(parser.screen());
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 4));
+        let mut state = PseudoTerminalState::default();
+        let pseudo_term = PseudoTerminal::new(&screen)
+            .alignment(Alignment::Right)
+            .vertical_alignment(VerticalAlignment::Bottom)
+            .viewport(Viewport::Bottom)
+            .fill_symbol("·");
+        StatefulWidget::render(pseudo_term, buf.area, &mut buf, &mut state);
+        assert_eq!(state.area(), Rect::new(2, 2, 4, 2));
+        assert_eq!(state.viewport_offset(), (0, 0));
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["······", "······", "··abcd", "··efgh"])
+        );
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+        let pseudo_term = PseudoTerminal::new(&screen).viewport(Viewport::Bottom);
+        StatefulWidget::render(pseudo_term, buf.area, &mut buf, &mut state);
+        assert_eq!(state.viewport_offset(), (1, 0));
+        assert_eq!(buf, Buffer::with_lines(["efgh"]));
+    }
+    #[test]
+    fn fill_style() {
+        let mut parser = vt100::Parser::new(2, 4, 0);
+        parser.process(b"abcd\r\nefgh\x1b[2;2H");
+        let mut state = PseudoTerminalState::default();
+        let pseudo_term = PseudoTerminal::new(parser.screen())
+            .style(Style::default().bg(Color::Blue))
+            .alignment(Alignment::Center)
+            .vertical_alignment(VerticalAlignment::Center)
+            .fill_style(Style::default().fg(Color::DarkGray).bg(Color::Black))
+            .fill_symbol("░");
+        let view = snapshot_stateful(pseudo_term, &mut state, 8, 4);
+        insta::assert_snapshot!(view);
+        assert_eq!(state.screen_position(Position::new(2, 1)), Some((0, 0)));
+        assert_eq!(state.screen_position(Position::new(1, 1)), None);
+        assert_eq!(state.cursor_position(), Some(Position::new(3, 2)));
+    }
+    #[test]
+    fn stateful_scrollback() {
+        let mut parser = vt100::Parser::new(5, 20, 100);
+        parser.process(b"line 1");
+        for line in 2..=10 {
+            parser.process(format!("\r\nline {line}").as_bytes());
+        }
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        let mut state = PseudoTerminalState::new();
+        state.scroll_up(4);
+        state.scroll_up(4);
+        let block = Block::default().borders(Borders::ALL);
+        let pseudo_term = PseudoTerminal::new(&screen).block(block);
+        let view = snapshot_stateful(pseudo_term, &mut state, 22, 7);
+        // The offset is clamped to the history while rendering
+        assert_eq!(state.scrollback(), 5);
+        assert_eq!(state.area(), Rect::new(1, 1, 20, 5));
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn selection_highlight() {
+        let stream = include_bytes!("../test/typescript/simple_ls.typescript");
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(stream);
+        let mut state = PseudoTerminalState::default();
+        state.start_selection((1, 10), SelectionMode::Linear);
+        state.extend_selection((2, 4));
+        let style = Style::default().bg(Color::Blue);
+        let pseudo_term = PseudoTerminal::new(parser.screen()).selection_style(style);
+        let view = snapshot_stateful(pseudo_term, &mut state, 80, 24);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn selection_follows_output() {
+        let mut parser = vt100::Parser::new(3, 10, 100);
+        parser.process(b"one\r\ntwo\r\nthree");
+        let mut state = PseudoTerminalState::default();
+        let screen = ScrollbackScreen::new(&mut parser, 0, 3);
+        snapshot_stateful(PseudoTerminal::new(&screen), &mut state, 10, 3);
+        state.start_selection((1, 0), SelectionMode::Linear);
+        state.extend_selection((1, 2));
+        // The selected row is pushed into the history by new output
+        parser.process(b"\r\nfour\r\nfive");
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        assert_eq!(state.selected_text(&screen).as_deref(), Some("two"));
+        // and stays selected when scrolled back into view
+        state.scroll_up(1);
+        snapshot_stateful(PseudoTerminal::new(&screen), &mut state, 10, 3);
+        state.extend_selection((1, 4));
+        assert_eq!(state.selected_text(&screen).as_deref(), Some("two\nthree"));
+    }
+    #[test]
+    fn selection_screen_position() {
+        let state = PseudoTerminalState {
+            area: Rect::new(1, 1, 20, 5),
+            ..Default::default()
+        };
+        assert_eq!(state.screen_position(Position::new(0, 0)), None);
+        assert_eq!(state.screen_position(Position::new(21, 1)), None);
+        assert_eq!(state.screen_position(Position::new(3, 2)), Some((1, 2)));
+    }
+    #[test]
+    fn search_highlight() {
+        let mut parser = vt100::Parser::new(5, 20, 100);
+        parser.process(b"line 1");
+        for line in 2..=10 {
+            parser.process(format!("\r\nline {line}").as_bytes());
+        }
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        let mut state = PseudoTerminalState::default();
+        state.start_search(&screen, SearchQuery::literal("line 2"));
+        // Not rendered yet, so the match is assumed to be out of view
+        let found = state.next_match(&screen).unwrap();
+        assert_eq!(found.start(), (1, 0));
+        assert_eq!(state.scrollback(), 4);
+        state.start_search(&screen, SearchQuery::literal("ne 3"));
+        let view = snapshot_stateful(PseudoTerminal::new(&screen), &mut state, 20, 5);
+        insta::assert_snapshot!(view);
+        // The match is visible, so the view is not scrolled
+        state.next_match(&screen);
+        assert_eq!(state.scrollback(), 4);
+    }
+    #[test]
+    fn search_reveals_match_in_viewport() {
+        let mut parser = vt100::Parser::new(5, 20, 100);
+        parser.process(b"line 1");
+        for line in 2..=10 {
+            parser.process(format!("\r\nline {line}").as_bytes());
+        }
+        let screen = ScrollbackScreen::new(&mut parser, usize::MAX, usize::MAX);
+        let mut state = PseudoTerminalState::default();
+        let pseudo_term = || PseudoTerminal::new(&screen).viewport(Viewport::Bottom);
+        snapshot_stateful(pseudo_term(), &mut state, 20, 2);
+        assert_eq!(state.viewport_offset(), (3, 0));
+        // The history alone does not reach the first line in the bottom rows
+        state.start_search(&screen, SearchQuery::literal("line 1"));
+        state.next_match(&screen);
+        let view = snapshot_stateful(pseudo_term(), &mut state, 20, 2);
+        assert_eq!((state.scrollback(), state.viewport_offset()), (5, (0, 0)));
+        assert!(view.contains("line 1 "));
+        // A match on the screen below the viewport
+        state.start_search(&screen, SearchQuery::literal("line 7"));
+        state.next_match(&screen);
+        let view = snapshot_stateful(pseudo_term(), &mut state, 20, 2);
+        assert!(view.contains("line 7"));
+        // Scrolling gives the rows back to the viewport
+        state.scroll_to_bottom();
+        snapshot_stateful(pseudo_term(), &mut state, 20, 2);
+        assert_eq!((state.scrollback(), state.viewport_offset()), (0, (3, 0)));
+    }
+    #[test]
+    fn palette_colors() {
+        let stream =
+            b"default [31mred[0m [44mblue[0m [38;5;196mindexed[0m [38;2;1;2;3mrgb[0m";
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(stream);
+        let pseudo_term = PseudoTerminal::new(parser.screen()).palette(Palette::solarized_dark());
+        let view = snapshot_widget(pseudo_term, 80, 24);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn color_depth_ansi16() {
+        let stream = b"[38;5;196mindexed[0m [48;2;0;0;120mrgb[0m";
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(stream);
+        let cursor = Cursor::default().style(Style::default().fg(Color::Rgb(255, 255, 0)));
+        let pseudo_term = PseudoTerminal::new(parser.screen())
+            .cursor(cursor)
+            .color_depth(ColorDepth::Ansi16);
+        let view = snapshot_widget(pseudo_term, 80, 24);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn color_depth_monochrome_overlays() {
+        let mut parser = vt100::Parser::new(2, 20, 0);
+        parser.process(b"plain \x1b[7mreversed\x1b[0m");
+        let mut state = PseudoTerminalState::default();
+        state.start_selection((0, 3), SelectionMode::Linear);
+        state.extend_selection((0, 8));
+        let pseudo_term = PseudoTerminal::new(parser.screen())
+            .selection_style(Style::default().bg(Color::Blue))
+            .color_depth(ColorDepth::Monochrome);
+        let view = snapshot_stateful(pseudo_term, &mut state, 20, 2);
+        insta::assert_snapshot!(view);
+    }
+    #[test]
+    fn cell_accessors_from_apply() {
+        // A cell written against the trait before it had accessors
+        struct ApplyOnly;
+        impl Cell for ApplyOnly {
+            fn has_contents(&self) -> bool {
+                true
+            }
+
+            fn apply(&self, cell: &mut ratatui::buffer::Cell) {
+                cell.set_symbol("x").set_style(
+                    Style::new()
+                        .fg(Color::Red)
+                        .bg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+        }
+        let cell = ApplyOnly;
+        assert_eq!(cell.symbol(), "x");
+        assert_eq!((cell.fg(), cell.bg()), (Color::Red, Color::Blue));
+        assert_eq!(cell.modifiers(), Modifier::BOLD);
+        assert!(!cell.is_wide());
+    }
+    #[test]
+    fn screen_queries() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(b"\x1b]1;icon\x07\x1b]2;title\x07");
+        parser.process(b"\x1b[?1h\x1b=\x1b[?2004h\x1b[?1002h\x1b[?1006h\x1b[?1049h");
+        let screen = parser.screen();
+        assert_eq!(Screen::size(screen), (24, 80));
+        assert_eq!(Screen::title(screen), "title");
+        assert_eq!(Screen::icon_name(screen), "icon");
+        assert!(Screen::alternate_screen(screen));
+        assert!(Screen::application_cursor(screen));
+        assert!(Screen::application_keypad(screen));
+        assert!(Screen::bracketed_paste(screen));
+        assert_eq!(
+            Screen::mouse_protocol_mode(screen),
+            MouseProtocolMode::ButtonMotion
+        );
+        assert_eq!(
+            Screen::mouse_protocol_encoding(screen),
+            MouseProtocolEncoding::Sgr
+        );
+    }
+    #[test]
+    fn cell_accessors() {
+        let mut parser = vt100::Parser::new(24, 80, 0);
+        parser.process(b"\x1b[1;4;31;44ma\x1b[0m\xe4\xb8\xad");
+        let screen = parser.screen();
+        let cell = Screen::cell(screen, 0, 0).unwrap();
+        assert_eq!(cell.symbol(), "a");
+        assert_eq!(Cell::fg(cell), Color::Indexed(1));
+        assert_eq!(Cell::bg(cell), Color::Indexed(4));
+        assert_eq!(cell.modifiers(), Modifier::BOLD | Modifier::UNDERLINED);
+        let wide = Screen::cell(screen, 0, 1).unwrap();
+        assert_eq!(wide.symbol(), "中");
+        assert!(Cell::is_wide(wide));
+        let continuation = Screen::cell(screen, 0, 2).unwrap();
+        assert_eq!(continuation.symbol(), " ");
+        assert!(Cell::is_wide_continuation(continuation));
+        let blank = Screen::cell(screen, 0, 3).unwrap();
+        assert!(!Cell::has_contents(blank));
+        assert_eq!(blank.symbol(), " ");
+    }
 }